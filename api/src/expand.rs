@@ -0,0 +1,222 @@
+use graph::TokenKind;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use spellcheck::{symspell::Verbosity, SpellCheck};
+use stemmer::{Frequency, Stemmer};
+use tokenizer::Tokenizer;
+
+// weights of each kind of alternative, the original term always wins
+const ROOT_WEIGHT: f32 = 0.8;
+const DERIVED_WEIGHT: f32 = 0.5;
+const SPELLING_WEIGHT: f32 = 0.6;
+
+#[derive(Debug, Deserialize)]
+pub struct ExpandParams {
+    #[serde(default)]
+    text: String,
+    /// document field the elasticsearch query matches against
+    #[serde(default = "default_field")]
+    field: String,
+}
+
+fn default_field() -> String {
+    "text".to_owned()
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Original,
+    Root,
+    Derived,
+    Spelling,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Alternative {
+    text: String,
+    weight: f32,
+    source: Source,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Term {
+    term: String,
+    alternatives: Vec<Alternative>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExpandResponse {
+    terms: Vec<Term>,
+    /// body for the elasticsearch _search endpoint
+    elasticsearch: Value,
+    /// synonyms for the meilisearch index settings
+    meilisearch: Value,
+}
+
+pub fn expand(
+    tokenizer: &Tokenizer,
+    stemmer: &Stemmer,
+    spellchecker: &SpellCheck,
+    params: ExpandParams,
+) -> ExpandResponse {
+    let graph = tokenizer.parse(params.text);
+    let mut terms: Vec<Term> = vec![];
+    for lexicon in &graph.lexicons {
+        for lexeme in lexicon.lexemes.iter() {
+            let word = graph.get_word(lexeme).to_lowercase();
+            let mut alternatives = vec![Alternative {
+                text: word.clone(),
+                weight: 1.0,
+                source: Source::Original,
+            }];
+            // numbers, urls and the like are searched as they are
+            if lexeme.metadata.kind == TokenKind::Word {
+                expand_word(stemmer, spellchecker, &word, &mut alternatives);
+            }
+            terms.push(Term {
+                term: word,
+                alternatives,
+            });
+        }
+    }
+    ExpandResponse {
+        elasticsearch: to_elasticsearch(&terms, &params.field),
+        meilisearch: to_meilisearch(&terms),
+        terms,
+    }
+}
+
+fn expand_word(
+    stemmer: &Stemmer,
+    spellchecker: &SpellCheck,
+    word: &str,
+    alternatives: &mut Vec<Alternative>,
+) {
+    let root = stemmer.stem_word_with(word, Some(spellchecker));
+    if let Some(root) = root {
+        push_alternative(alternatives, root, ROOT_WEIGHT, Source::Root);
+        // only forms found in the corpus, most of the templates don't apply
+        for derived in stemmer.generate_all(root) {
+            if spellchecker.frequency(&derived) > 0 {
+                push_alternative(alternatives, &derived, DERIVED_WEIGHT, Source::Derived);
+            }
+        }
+    }
+    for suggestion in spellchecker.lookup_word_with(word, Verbosity::Closest) {
+        let weight = SPELLING_WEIGHT / (1 + suggestion.distance) as f32;
+        push_alternative(alternatives, &suggestion.term, weight, Source::Spelling);
+    }
+}
+
+// keeps the highest weight when the same text comes from several sources
+fn push_alternative(alternatives: &mut Vec<Alternative>, text: &str, weight: f32, source: Source) {
+    match alternatives.iter_mut().find(|a| a.text == text) {
+        Some(a) if a.weight < weight => {
+            a.weight = weight;
+            a.source = source;
+        }
+        Some(_) => {}
+        None => alternatives.push(Alternative {
+            text: text.to_owned(),
+            weight,
+            source,
+        }),
+    }
+}
+
+// every term must match through one of its alternatives
+fn to_elasticsearch(terms: &[Term], field: &str) -> Value {
+    let must: Vec<Value> = terms
+        .iter()
+        .map(|term| {
+            let should: Vec<Value> = term
+                .alternatives
+                .iter()
+                .map(|a| json!({ "match": { field: { "query": a.text, "boost": a.weight } } }))
+                .collect();
+            json!({ "bool": { "should": should, "minimum_should_match": 1 } })
+        })
+        .collect();
+    json!({ "query": { "bool": { "must": must } } })
+}
+
+fn to_meilisearch(terms: &[Term]) -> Value {
+    let mut synonyms = Map::new();
+    for term in terms {
+        let alternatives: Vec<&str> = term
+            .alternatives
+            .iter()
+            .filter(|a| a.source != Source::Original)
+            .map(|a| a.text.as_str())
+            .collect();
+        if !alternatives.is_empty() {
+            synonyms.insert(term.term.clone(), json!(alternatives));
+        }
+    }
+    json!({ "synonyms": synonyms })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let tokenizer = Tokenizer::new();
+        let stemmer = Stemmer::new();
+        let spellchecker = SpellCheck::new();
+        let params = ExpandParams {
+            text: "kvcing 2024".to_owned(),
+            field: "judul".to_owned(),
+        };
+        let response = expand(&tokenizer, &stemmer, &spellchecker, params);
+        let alternatives = &response.terms[0].alternatives;
+        assert_eq!(alternatives[0].text, "kvcing");
+        assert_eq!(alternatives[0].source, Source::Original);
+        let kucing = alternatives.iter().find(|a| a.text == "kucing").unwrap();
+        assert_eq!(kucing.source, Source::Spelling);
+        assert_eq!(kucing.weight, SPELLING_WEIGHT / 2.0);
+        // numbers are searched as they are
+        assert_eq!(response.terms[1].alternatives.len(), 1);
+
+        let must = &response.elasticsearch["query"]["bool"]["must"];
+        assert_eq!(must.as_array().unwrap().len(), 2);
+        assert_eq!(must[0]["bool"]["minimum_should_match"], 1);
+        let should = must[0]["bool"]["should"].as_array().unwrap();
+        assert_eq!(should.len(), alternatives.len());
+        assert_eq!(should[0]["match"]["judul"]["query"], "kvcing");
+        assert_eq!(
+            must[1]["bool"]["should"],
+            json!([{ "match": { "judul": { "query": "2024", "boost": 1.0 } } }])
+        );
+        let synonyms = &response.meilisearch["synonyms"];
+        assert!(synonyms["kvcing"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("kucing")));
+        assert!(synonyms.get("2024").is_none());
+
+        // accented words reach the stemmer too
+        let params = ExpandParams {
+            text: "kopi café".to_owned(),
+            field: "judul".to_owned(),
+        };
+        let response = expand(&tokenizer, &stemmer, &spellchecker, params);
+        assert_eq!(response.terms[1].alternatives[0].text, "café");
+
+        // the highest weight wins when several sources give the same text
+        let mut alternatives = vec![];
+        push_alternative(
+            &mut alternatives,
+            "makan",
+            SPELLING_WEIGHT,
+            Source::Spelling,
+        );
+        push_alternative(&mut alternatives, "makan", ROOT_WEIGHT, Source::Root);
+        push_alternative(&mut alternatives, "makan", DERIVED_WEIGHT, Source::Derived);
+        assert_eq!(alternatives.len(), 1);
+        assert_eq!(alternatives[0].weight, ROOT_WEIGHT);
+        assert_eq!(alternatives[0].source, Source::Root);
+    }
+}
@@ -6,26 +6,36 @@ use axum::{
 };
 use axum_swagger_ui::swagger_ui;
 use deepsize::DeepSizeOf;
+use expand::ExpandParams;
 use georesolv::Postal;
-use graph::{Graph, Lexicon};
+use grammar::{Diagnostic, Grammar};
+use graph::{Graph, Lexicon, TokenKind};
 use hyper::{
     header::{self, ACCEPT, CONTENT_TYPE},
     http::HeaderValue,
-    Method, Server,
+    Method, Server, StatusCode,
 };
+use langid::Langid;
+use moderation::{Flag, Moderation};
+use ner::{Entity, Ner};
+use normalizer::Normalizer;
 use once_cell::sync::Lazy;
-use serde::Deserialize;
-use spellcheck::SpellCheck;
+use sentiment::{Analysis, Sentiment};
+use serde::{Deserialize, Serialize};
+use spellcheck::{Correction, LookupOptions, SpellCheck};
 use std::time::Instant;
 use std::{
     env,
     net::SocketAddr,
     sync::{Arc, Mutex},
 };
-use stemmer::Stemmer;
-use tokenizer::Tokenizer;
+use stemmer::{Engine, RankedStem, StemOptions, Stemmer, StopWordOptions, UnknownPolicy};
+use tagger::Tagger;
+use tokenizer::{fits, Tokenizer};
 use tower_http::cors::{Any, CorsLayer};
 
+mod expand;
+
 #[derive(Debug, Deserialize)]
 struct Params {
     #[serde(default)]
@@ -34,44 +44,273 @@ struct Params {
     tasks: Vec<String>,
     #[serde(default)]
     lexicons: Option<Vec<Lexicon>>,
+    // text the lexicons were made for, only the edited part is parsed again
+    #[serde(default)]
+    previous_text: Option<String>,
+    #[serde(default)]
+    preserve_layout: bool,
+    #[serde(default)]
+    engine: Engine,
+    #[serde(default)]
+    stop_words: StopWordOptions,
+    #[serde(default)]
+    unknown: UnknownPolicy,
 }
 
-static TOKENIZER_ENGINE: Lazy<Arc<Mutex<(Tokenizer, Stemmer, SpellCheck)>>> =
-    Lazy::new(|| init_tokenizer());
+#[derive(Debug, Deserialize)]
+struct DiffParams {
+    #[serde(default)]
+    old: String,
+    #[serde(default)]
+    new: String,
+    // lexicons with hash keys, from earlier responses
+    #[serde(default)]
+    old_lexicons: Option<Vec<Lexicon>>,
+    #[serde(default)]
+    new_lexicons: Option<Vec<Lexicon>>,
+}
+
+#[derive(Serialize)]
+struct TokenizeResponse<'a> {
+    #[serde(flatten)]
+    graph: &'a Graph,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grammar: Option<Vec<Diagnostic>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    corrections: Option<Vec<Correction>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entities: Option<Vec<Entity>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sentiment: Option<Analysis>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flags: Option<Vec<Flag>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    masked: Option<String>,
+}
+
+#[derive(Serialize)]
+struct StemsResponse {
+    word: String,
+    candidates: Vec<RankedStem>,
+}
+
+// number of alternatives listed for each correction
+const MAX_ALTERNATIVES: usize = 5;
+
+// Tasks run in the order they are listed, but the first of each pair has to
+// come before the second. Spellcheck would fight the normalizer over
+// informal words it has not replaced yet, and the words "mask" finds have to
+// be in the text it renders.
+const TASK_ORDER: &[(&str, &str)] = &[
+    ("normalize", "spellcheck"),
+    ("normalize", "mask"),
+    ("spellcheck", "mask"),
+    ("stemming", "mask"),
+    ("stopwords", "mask"),
+];
+
+// tasks which change the text, moving every offset found before them
+const TEXT_TASKS: &[&str] = &["normalize", "spellcheck", "stemming", "stopwords"];
+
+// the first pair of tasks which is listed the wrong way round
+fn misordered(tasks: &[String]) -> Option<(&'static str, &'static str)> {
+    let position = |task: &str| tasks.iter().position(|t| t == task);
+    TASK_ORDER.iter().copied().find(|(first, second)| {
+        matches!((position(first), position(second)), (Some(a), Some(b)) if a > b)
+    })
+}
+
+type TokenizerEngine = (
+    Tokenizer,
+    Arc<Stemmer>,
+    SpellCheck,
+    Arc<Tagger>,
+    Grammar,
+    Normalizer,
+    Langid,
+    Ner,
+    Sentiment,
+    Moderation,
+);
+
+static TOKENIZER_ENGINE: Lazy<Arc<Mutex<TokenizerEngine>>> = Lazy::new(|| init_tokenizer());
 
 static RESOLVER_ENGINE: Lazy<Arc<Mutex<Postal>>> = Lazy::new(|| init_resolver());
 
 async fn tokenizer(Json(payload): Json<Params>) -> impl IntoResponse {
     // Here you can handle the POST request, for example:
     let mutex = &*TOKENIZER_ENGINE.lock().unwrap();
-    let (tokenizer, stemmer, spellchecker) = mutex;
+    let (
+        tokenizer,
+        stemmer,
+        spellchecker,
+        tagger,
+        grammar,
+        normalizer,
+        langid,
+        ner,
+        sentiment,
+        moderation,
+    ) = mutex;
 
-    let mut body = match payload.lexicons {
-        None => tokenizer.parse(payload.text),
-        Some(t) => Graph {
+    if let Some((first, second)) = misordered(&payload.tasks) {
+        let message = format!("Task \"{}\" harus sebelum \"{}\"", first, second);
+        let mut res = Response::new(message);
+        *res.status_mut() = StatusCode::BAD_REQUEST;
+        return res;
+    }
+    if let Some(list) = &payload.stop_words.list {
+        if stemmer.get_stop_list(list).is_none() {
+            let mut res = Response::new(format!("Daftar stop word \"{}\" tidak ditemukan", list));
+            *res.status_mut() = StatusCode::BAD_REQUEST;
+            return res;
+        }
+    }
+    let mut body = match (payload.lexicons, payload.previous_text) {
+        (None, _) => tokenizer.parse(payload.text),
+        (Some(t), None) => Graph {
             text: payload.text,
             lexicons: t,
             using_keys: true,
+            sentences: vec![],
         },
+        (Some(t), Some(previous)) => {
+            let previous = Graph {
+                text: previous,
+                lexicons: t,
+                using_keys: true,
+                sentences: vec![],
+            };
+            match tokenizer.reparse(&previous, payload.text) {
+                Some(graph) => graph,
+                None => {
+                    let mut res =
+                        Response::new("Lexicons tidak cocok dengan previous_text".to_owned());
+                    *res.status_mut() = StatusCode::BAD_REQUEST;
+                    return res;
+                }
+            }
+        }
+    };
+    // stop words are only dropped by the "stopwords" task
+    let keep_stop_words = StopWordOptions {
+        enabled: false,
+        ..Default::default()
+    };
+    let lookup_options = LookupOptions {
+        preserve_layout: payload.preserve_layout,
+    };
+    let stem_options = StemOptions {
+        preserve_layout: payload.preserve_layout,
+        frequency: Some(spellchecker),
+        stop_words: Some(&keep_stop_words),
+        unknown: payload.unknown,
+        engine: Some(payload.engine),
     };
     let mut need_tokenized_output = false;
-    for task in payload.tasks {
+    let mut diagnostics = None;
+    let mut corrections = None;
+    let mut language = None;
+    let mut entities = None;
+    let mut analysis = None;
+    let mut flags = None;
+    // whether the flags still point into the current text
+    let mut flags_current = false;
+    let mut masked = None;
+    let tasks = payload.tasks;
+    let mut stop_words = payload.stop_words;
+    // "sentiment" after "stopwords" still needs "tidak" or "sangat"
+    let position = |task: &str| tasks.iter().position(|t| t == task);
+    if matches!((position("stopwords"), position("sentiment")), (Some(a), Some(b)) if a < b) {
+        stop_words
+            .remove
+            .extend(Sentiment::modifiers().map(|w| w.to_owned()));
+    }
+    for task in tasks {
+        if TEXT_TASKS.contains(&task.as_str()) {
+            flags_current = false;
+        }
         match task.as_str() {
-            "spellcheck" => body = spellchecker.lookup_graph(&body),
-            "stemming" => body = stemmer.stem_graph(&body),
+            "normalize" => body = normalizer.normalize_graph(&body),
+            "spellcheck" => body = spellchecker.lookup_graph_with(&body, lookup_options),
+            "stemming" => body = stemmer.stem_graph_with(&body, stem_options),
+            "stopwords" => body = stemmer.remove_stop_words(&body, &stop_words),
+            "langid" => {
+                language = langid.tag_graph(&mut body);
+                need_tokenized_output = true
+            }
+            "ner" => {
+                entities = Some(ner.recognize_graph(&body));
+                need_tokenized_output = true
+            }
+            "sentiment" => {
+                analysis = Some(sentiment.analyze_graph(&body));
+                need_tokenized_output = true
+            }
+            "moderation" => {
+                flags = Some(moderation.check_graph(&body, Some(spellchecker)));
+                flags_current = true;
+                need_tokenized_output = true
+            }
+            "mask" => {
+                let checked;
+                let found = match &flags {
+                    Some(found) if flags_current => found,
+                    _ => {
+                        checked = moderation.check_graph(&body, Some(spellchecker));
+                        &checked
+                    }
+                };
+                let spans: Vec<(usize, usize)> = found
+                    .iter()
+                    .filter(|f| f.category.is_abusive())
+                    .map(|f| (f.offset, f.length))
+                    .collect();
+                masked = Some(spans)
+            }
+            "pos" => tagger.tag_graph(&mut body),
+            "morphology" => {
+                stemmer.analyze_graph(&mut body, Some(spellchecker));
+                need_tokenized_output = true
+            }
+            "grammar" => {
+                diagnostics = Some(grammar.check_graph(&body));
+                need_tokenized_output = true
+            }
+            "corrections" => {
+                corrections = Some(spellchecker.lookup_details(&body, MAX_ALTERNATIVES));
+                need_tokenized_output = true
+            }
+            "sentences" => {
+                tokenizer.segment(&mut body);
+                need_tokenized_output = true
+            }
             "tokenize" => need_tokenized_output = true,
             "init_keys" => body.init_hash_keys(),
             _ => {}
         }
     }
+    let masked = masked.map(|spans| tokenizer.render_masked(&body, &spans));
     if need_tokenized_output {
-        let body_str = serde_json::to_string(&body).unwrap();
+        let response = TokenizeResponse {
+            graph: &body,
+            grammar: diagnostics,
+            corrections,
+            language,
+            entities,
+            sentiment: analysis,
+            flags,
+            masked,
+        };
+        let body_str = serde_json::to_string(&response).unwrap();
         let mut res = Response::new(body_str);
         let mime = HeaderValue::from_static("application/json");
         res.headers_mut().insert(header::CONTENT_TYPE, mime);
         res
     } else {
-        let body_str = tokenizer.render(&body);
+        let body_str = masked.unwrap_or_else(|| tokenizer.render(&body));
         let mut res = Response::new(body_str);
         let mime = HeaderValue::from_static("text/plain");
         res.headers_mut().insert(header::CONTENT_TYPE, mime);
@@ -79,6 +318,83 @@ async fn tokenizer(Json(payload): Json<Params>) -> impl IntoResponse {
     }
 }
 
+async fn expand(Json(payload): Json<ExpandParams>) -> impl IntoResponse {
+    let mutex = &*TOKENIZER_ENGINE.lock().unwrap();
+    let (tokenizer, stemmer, spellchecker, _, _, _, _, _, _, _) = mutex;
+    let body = expand::expand(tokenizer, stemmer, spellchecker, payload);
+    let body_str = serde_json::to_string(&body).unwrap();
+    let mut res = Response::new(body_str);
+    let mime = HeaderValue::from_static("application/json");
+    res.headers_mut().insert(header::CONTENT_TYPE, mime);
+    res
+}
+
+async fn stems(Json(payload): Json<Params>) -> impl IntoResponse {
+    let mutex = &*TOKENIZER_ENGINE.lock().unwrap();
+    let (tokenizer, stemmer, spellchecker, _, _, _, _, _, _, _) = mutex;
+    let graph = tokenizer.parse(payload.text);
+    let mut body = vec![];
+    for lexicon in &graph.lexicons {
+        for lexeme in lexicon.lexemes.iter() {
+            if lexeme.metadata.kind != TokenKind::Word {
+                continue;
+            }
+            let word = graph.get_word(lexeme);
+            body.push(StemsResponse {
+                word: word.to_owned(),
+                candidates: stemmer.rank_stems(word, Some(spellchecker)),
+            });
+        }
+    }
+    let body_str = serde_json::to_string(&body).unwrap();
+    let mut res = Response::new(body_str);
+    let mime = HeaderValue::from_static("application/json");
+    res.headers_mut().insert(header::CONTENT_TYPE, mime);
+    res
+}
+
+async fn identities(Json(payload): Json<Params>) -> impl IntoResponse {
+    let mutex = &*TOKENIZER_ENGINE.lock().unwrap();
+    let (tokenizer, _, _, _, _, _, _, ner, _, _) = mutex;
+    let graph = tokenizer.parse(payload.text);
+    let body = ner.identities_graph(&graph);
+    let body_str = serde_json::to_string(&body).unwrap();
+    let mut res = Response::new(body_str);
+    let mime = HeaderValue::from_static("application/json");
+    res.headers_mut().insert(header::CONTENT_TYPE, mime);
+    res
+}
+
+async fn diff(Json(payload): Json<DiffParams>) -> impl IntoResponse {
+    // the engine is only locked while parsing, aligning needs none of it
+    let graph = |text: String, lexicons: Option<Vec<Lexicon>>| match lexicons {
+        None => {
+            let mutex = &*TOKENIZER_ENGINE.lock().unwrap();
+            let (tokenizer, _, _, _, _, _, _, _, _, _) = mutex;
+            tokenizer.parse(text)
+        }
+        Some(t) => Graph {
+            text,
+            lexicons: t,
+            using_keys: true,
+            sentences: vec![],
+        },
+    };
+    let old = graph(payload.old, payload.old_lexicons);
+    let new = graph(payload.new, payload.new_lexicons);
+    if !fits(&old) || !fits(&new) {
+        let mut res = Response::new("Lexicons tidak cocok dengan teksnya".to_owned());
+        *res.status_mut() = StatusCode::BAD_REQUEST;
+        return res;
+    }
+    let body = old.diff(&new);
+    let body_str = serde_json::to_string(&body).unwrap();
+    let mut res = Response::new(body_str);
+    let mime = HeaderValue::from_static("application/json");
+    res.headers_mut().insert(header::CONTENT_TYPE, mime);
+    res
+}
+
 async fn postal(Json(payload): Json<Params>) -> impl IntoResponse {
     let mutex = &*RESOLVER_ENGINE.lock().unwrap();
     let body = mutex.parse(&payload.text);
@@ -89,11 +405,19 @@ async fn postal(Json(payload): Json<Params>) -> impl IntoResponse {
     res
 }
 
-fn init_tokenizer() -> Arc<Mutex<(Tokenizer, Stemmer, SpellCheck)>> {
+fn init_tokenizer() -> Arc<Mutex<TokenizerEngine>> {
     let start = Instant::now();
     let tokenizer = Tokenizer::new();
-    let stemmer = Stemmer::new();
-    let spellcheck = SpellCheck::new();
+    // one stemmer for all of them, its tables are large
+    let stemmer = Arc::new(Stemmer::new());
+    let spellcheck = SpellCheck::with_stemmer(stemmer.clone());
+    let tagger = Arc::new(Tagger::with_stemmer(stemmer.clone()));
+    let grammar = Grammar::with_tagger(tagger.clone());
+    let normalizer = Normalizer::new();
+    let langid = Langid::new();
+    let ner = Ner::new();
+    let sentiment = Sentiment::new();
+    let moderation = Moderation::new();
     let duration = start.elapsed();
 
     println!("Initialization took: {:.2?} seconds", duration);
@@ -103,7 +427,10 @@ fn init_tokenizer() -> Arc<Mutex<(Tokenizer, Stemmer, SpellCheck)>> {
     );
     spellcheck.debug_heap();
 
-    Arc::new(Mutex::new((tokenizer, stemmer, spellcheck)))
+    Arc::new(Mutex::new((
+        tokenizer, stemmer, spellcheck, tagger, grammar, normalizer, langid, ner, sentiment,
+        moderation,
+    )))
 }
 
 fn init_resolver() -> Arc<Mutex<Postal>> {
@@ -155,6 +482,10 @@ async fn main() {
         .route("/", get(Redirect::to("/swagger")))
         .route("/", post(tokenizer).layer(cors.clone()))
         .route("/tokenize", post(tokenizer).layer(cors.clone()))
+        .route("/stems", post(stems).layer(cors.clone()))
+        .route("/expand", post(expand).layer(cors.clone()))
+        .route("/identities", post(identities).layer(cors.clone()))
+        .route("/diff", post(diff).layer(cors.clone()))
         .route("/postal", post(postal).layer(cors.clone()))
         .route("/health", get(health));
     let addr_str = env::var("LISTEN").unwrap_or_else(|_| "127.0.0.1:3000".to_string());
@@ -1,5 +1,78 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
+use std::sync::Arc;
+
+use graph::Graph;
+use serde::Serialize;
+use tagger::Tagger;
+use tokenizer::Tokenizer;
+
+mod rules;
+
+pub struct Grammar {
+    tokenizer: Tokenizer,
+    tagger: Arc<Tagger>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Rule {
+    /// "dirumah" should be "di rumah"
+    PrepositionJoined,
+    /// "di makan" should be "dimakan"
+    PrefixSeparated,
+    /// "para siswa-siswa" should be "para siswa"
+    RedundantPlural,
+    /// a sentence should start with a capital letter
+    Capitalization,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub rule: Rule,
+    /// byte offset into `Graph.text`
+    pub offset: usize,
+    /// byte length of the offending text
+    pub length: usize,
+    pub message: String,
+    pub replacements: Vec<String>,
+}
+
+impl Default for Grammar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Grammar {
+    // Initialization function
+    pub fn new() -> Self {
+        Grammar::with_tagger(Arc::new(Tagger::new()))
+    }
+
+    /// Shares a tagger, and the stemmer in it, which is already loaded elsewhere.
+    pub fn with_tagger(tagger: Arc<Tagger>) -> Self {
+        Grammar {
+            tokenizer: Tokenizer::new(),
+            tagger,
+        }
+    }
+
+    pub fn check(&self, text: &str) -> Vec<Diagnostic> {
+        let graph = self.tokenizer.parse(text.to_owned());
+        self.check_graph(&graph)
+    }
+
+    pub fn check_graph(&self, graph: &Graph) -> Vec<Diagnostic> {
+        let sentences = match graph.sentences.is_empty() {
+            true => self.tokenizer.find_sentences(graph),
+            false => graph.sentences.clone(),
+        };
+        let tokens = rules::Token::collect(graph, &sentences);
+        let mut diagnostics = vec![];
+        self.check_prepositions(&tokens, &mut diagnostics);
+        self.check_plurals(&tokens, &mut diagnostics);
+        self.check_capitalization(&tokens, &mut diagnostics);
+        diagnostics.sort_by_key(|d| d.offset);
+        diagnostics
+    }
 }
 
 #[cfg(test)]
@@ -8,7 +81,35 @@ mod tests {
 
     #[test]
     fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+        let grammar = Grammar::new();
+        let text = "saya tinggal dirumah. Para siswa-siswa pergi kesana. nasinya di makan kucing.";
+        let diagnostics = grammar.check(text);
+        let found: Vec<(Rule, &str, &str)> = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.rule,
+                    &text[d.offset..d.offset + d.length],
+                    d.replacements[0].as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Rule::Capitalization, "saya", "Saya"),
+                (Rule::PrepositionJoined, "dirumah", "di rumah"),
+                (Rule::RedundantPlural, "siswa-siswa", "siswa"),
+                (Rule::PrepositionJoined, "kesana", "ke sana"),
+                (Rule::Capitalization, "nasinya", "Nasinya"),
+                (Rule::PrefixSeparated, "di makan", "dimakan"),
+            ]
+        );
+        assert_eq!(grammar.check("Saya makan di rumah."), vec![]);
+        assert_eq!(
+            grammar.check("Ia membeli pensil, buku, dll. untuk sekolah."),
+            vec![]
+        );
+        assert_eq!(grammar.check("https://benerin.web.id bagus."), vec![]);
     }
 }
@@ -0,0 +1,135 @@
+use graph::{Graph, PosTagging, Sentence, TokenKind};
+
+use crate::{Diagnostic, Grammar, Rule};
+
+// words that already mark a plural, so the noun after them is not repeated
+#[rustfmt::skip]
+const QUANTIFIERS: &[&str] = &[
+    "para", "banyak", "beberapa", "semua", "berbagai", "sejumlah", "segala", "seluruh", "segenap",
+];
+
+pub struct Token<'a> {
+    pub word: &'a str,
+    pub offset: usize,
+    pub length: usize,
+    pub sentence_start: bool,
+    pub kind: TokenKind,
+    // index of the lexicon, tokens in different lexicons are split by punctuation
+    pub lexicon: usize,
+}
+
+impl<'a> Token<'a> {
+    pub fn collect(graph: &'a Graph, sentences: &[Sentence]) -> Vec<Token<'a>> {
+        let mut tokens = vec![];
+        let mut sentence_start = true;
+        let mut sentences = sentences.iter().peekable();
+        for (i, lexicon) in graph.lexicons.iter().enumerate() {
+            if sentences.next_if(|s| s.lexicon == i).is_some() {
+                sentence_start = true;
+            }
+            for lexeme in lexicon.lexemes.iter() {
+                tokens.push(Token {
+                    word: graph.get_word(lexeme),
+                    offset: lexeme.offset,
+                    length: lexeme.length,
+                    sentence_start,
+                    kind: lexeme.metadata.kind,
+                    lexicon: i,
+                });
+                sentence_start = false;
+            }
+        }
+        tokens
+    }
+}
+
+impl Grammar {
+    pub(crate) fn check_prepositions(&self, tokens: &[Token], diagnostics: &mut Vec<Diagnostic>) {
+        let stemmer = self.tagger.stemmer();
+        for (i, token) in tokens.iter().enumerate() {
+            if token.kind != TokenKind::Word {
+                continue;
+            }
+            if let Some((p, rest)) = stemmer.split_preposition(token.word) {
+                diagnostics.push(Diagnostic {
+                    rule: Rule::PrepositionJoined,
+                    offset: token.offset,
+                    length: token.length,
+                    message: format!(
+                        "Kata depan \"{}\" ditulis terpisah dari kata yang mengikutinya",
+                        p.to_lowercase()
+                    ),
+                    replacements: vec![format!("{} {}", p, rest)],
+                });
+                continue;
+            }
+            let next = match tokens.get(i + 1) {
+                Some(next) if next.lexicon == token.lexicon && next.kind == TokenKind::Word => next,
+                _ => continue,
+            };
+            let joined = match stemmer.join_preposition(token.word, next.word) {
+                Some(joined) => joined,
+                None => continue,
+            };
+            // "di" before a noun is still a preposition (di meja)
+            if token.word.eq_ignore_ascii_case("di")
+                && self.tagger.tag_word(next.word) != PosTagging::Verb
+            {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                rule: Rule::PrefixSeparated,
+                offset: token.offset,
+                length: next.offset + next.length - token.offset,
+                message: format!(
+                    "Awalan \"{}\" ditulis serangkai dengan kata dasarnya",
+                    token.word.to_lowercase()
+                ),
+                replacements: vec![joined],
+            });
+        }
+    }
+
+    pub(crate) fn check_plurals(&self, tokens: &[Token], diagnostics: &mut Vec<Diagnostic>) {
+        for pair in tokens.windows(2) {
+            let (quantifier, noun) = (&pair[0], &pair[1]);
+            if !QUANTIFIERS.contains(&quantifier.word.to_lowercase().as_str()) {
+                continue;
+            }
+            let single = match noun.word.split_once('-') {
+                Some((a, b)) if a.eq_ignore_ascii_case(b) => a,
+                _ => continue,
+            };
+            diagnostics.push(Diagnostic {
+                rule: Rule::RedundantPlural,
+                offset: noun.offset,
+                length: noun.length,
+                message: format!(
+                    "Kata \"{}\" sudah menyatakan jamak, kata ulang tidak diperlukan",
+                    quantifier.word.to_lowercase()
+                ),
+                replacements: vec![single.to_owned()],
+            });
+        }
+    }
+
+    pub(crate) fn check_capitalization(&self, tokens: &[Token], diagnostics: &mut Vec<Diagnostic>) {
+        let starts = tokens
+            .iter()
+            .filter(|t| t.sentence_start && t.kind == TokenKind::Word);
+        for token in starts {
+            let mut chars = token.word.chars();
+            let first = match chars.next() {
+                Some(c) if c.is_lowercase() => c,
+                _ => continue,
+            };
+            diagnostics.push(Diagnostic {
+                rule: Rule::Capitalization,
+                offset: token.offset,
+                length: token.length,
+                message: "Awal kalimat ditulis dengan huruf kapital".to_owned(),
+                replacements: vec![first.to_uppercase().chain(chars).collect()],
+            });
+        }
+    }
+}
@@ -0,0 +1,73 @@
+/// Capitalisation pattern of a word, used to carry the pattern of the
+/// original word over to its correction.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Casing {
+    /// "rumah"
+    Lower,
+    /// "RUMAH"
+    Upper,
+    /// "Rumah"
+    Title,
+    /// anything else, e.g. "iPhone"
+    Mixed,
+}
+
+impl Casing {
+    pub fn detect(word: &str) -> Casing {
+        let mut letters = word.chars().filter(|c| c.is_alphabetic());
+        let first = match letters.next() {
+            Some(c) => c,
+            None => return Casing::Lower,
+        };
+        let (mut upper, mut lower) = (0, 0);
+        for c in letters {
+            if c.is_uppercase() {
+                upper += 1;
+            } else {
+                lower += 1;
+            }
+        }
+        match (first.is_uppercase(), upper, lower) {
+            (false, 0, _) => Casing::Lower,
+            // single letter words are title cased rather than upper
+            (true, 0, _) => Casing::Title,
+            (true, _, 0) => Casing::Upper,
+            _ => Casing::Mixed,
+        }
+    }
+
+    /// Applies the pattern to a word. Mixed words are returned unchanged.
+    pub fn apply(&self, word: &str) -> String {
+        match self {
+            Casing::Lower => word.to_lowercase(),
+            Casing::Upper => word.to_uppercase(),
+            Casing::Title => {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(c) => c
+                        .to_uppercase()
+                        .chain(chars.flat_map(char::to_lowercase))
+                        .collect(),
+                    None => String::new(),
+                }
+            }
+            Casing::Mixed => word.to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        assert_eq!(Casing::detect("rumah"), Casing::Lower);
+        assert_eq!(Casing::detect("RUMAH"), Casing::Upper);
+        assert_eq!(Casing::detect("Rumah"), Casing::Title);
+        assert_eq!(Casing::detect("A"), Casing::Title);
+        assert_eq!(Casing::detect("iPhone"), Casing::Mixed);
+        assert_eq!(Casing::Title.apply("di rumah"), "Di rumah");
+        assert_eq!(Casing::Upper.apply("kucing"), "KUCING");
+    }
+}
@@ -0,0 +1,252 @@
+// Word level differences between two graphs. Lexemes are aligned by their
+// hash keys when both graphs use keys, by their words otherwise.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Graph, Lexeme};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Insert,
+    Delete,
+    Replace,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub operation: Operation,
+    /// byte offset into the old text, where the words go for an insert
+    pub old_offset: usize,
+    pub old_length: usize,
+    /// byte offset into the new text, where the words were for a delete
+    pub new_offset: usize,
+    pub new_length: usize,
+    pub old_text: String,
+    pub new_text: String,
+    /// keys of the changed lexemes, when both graphs use keys
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub old_keys: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub new_keys: Vec<usize>,
+}
+
+impl Graph {
+    /// Changes which turn this graph into `other`, in text order. A lexeme
+    /// which kept its key but not its word is a replace of its own.
+    pub fn diff(&self, other: &Graph) -> Vec<Change> {
+        let old: Vec<&Lexeme> = self.lexicons.iter().flat_map(|l| &l.lexemes).collect();
+        let new: Vec<&Lexeme> = other.lexicons.iter().flat_map(|l| &l.lexemes).collect();
+        let by_key = self.using_keys && other.using_keys;
+        let pairs = align(old.len(), new.len(), |i, j| match by_key {
+            true => old[i].metadata.key != 0 && old[i].metadata.key == new[j].metadata.key,
+            false => self.get_word(old[i]) == other.get_word(new[j]),
+        });
+        let mut changes = vec![];
+        let (mut i, mut j) = (0, 0);
+        // the end of both texts closes the last run of changes
+        for (a, b) in pairs.into_iter().chain([(old.len(), new.len())]) {
+            if a > i || b > j {
+                let old_at = old.get(a).map_or(self.text.len(), |l| l.offset);
+                let new_at = new.get(b).map_or(other.text.len(), |l| l.offset);
+                changes.push(self.change(other, &old[i..a], &new[j..b], old_at, new_at));
+            }
+            if a < old.len() && self.get_word(old[a]) != other.get_word(new[b]) {
+                let (old_at, new_at) = (old[a].offset, new[b].offset);
+                changes.push(self.change(other, &old[a..=a], &new[b..=b], old_at, new_at));
+            }
+            (i, j) = (a + 1, b + 1);
+        }
+        changes
+    }
+
+    fn change(
+        &self,
+        other: &Graph,
+        old: &[&Lexeme],
+        new: &[&Lexeme],
+        old_at: usize,
+        new_at: usize,
+    ) -> Change {
+        let (old_offset, old_end) = span(old, old_at);
+        let (new_offset, new_end) = span(new, new_at);
+        let operation = match (old.is_empty(), new.is_empty()) {
+            (true, _) => Operation::Insert,
+            (_, true) => Operation::Delete,
+            _ => Operation::Replace,
+        };
+        let keys = |lexemes: &[&Lexeme]| match self.using_keys && other.using_keys {
+            true => lexemes.iter().map(|l| l.metadata.key).collect(),
+            false => vec![],
+        };
+        Change {
+            operation,
+            old_offset,
+            old_length: old_end - old_offset,
+            new_offset,
+            new_length: new_end - new_offset,
+            old_text: self.text[old_offset..old_end].to_owned(),
+            new_text: other.text[new_offset..new_end].to_owned(),
+            old_keys: keys(old),
+            new_keys: keys(new),
+        }
+    }
+}
+
+// from the start of the first lexeme to the end of the last one, or empty at `at`
+fn span(lexemes: &[&Lexeme], at: usize) -> (usize, usize) {
+    match (lexemes.first(), lexemes.last()) {
+        (Some(first), Some(last)) => (first.offset, last.offset + last.length),
+        _ => (at, at),
+    }
+}
+
+// the trace grows with the square of the inserted and deleted words, past
+// this many the texts are too different and all of it is one replace
+const MAX_EDITS: isize = 1000;
+
+// Myers' algorithm, the matched (old, new) indices of a shortest edit script
+fn align(n: usize, m: usize, same: impl Fn(usize, usize) -> bool) -> Vec<(usize, usize)> {
+    let max = (n + m) as isize;
+    let at = |k: isize| (k + max + 1) as usize;
+    // furthest x reached on each diagonal k = x - y
+    let mut v = vec![0isize; 2 * (n + m) + 3];
+    // v on diagonals -d..=d before each round d
+    let mut trace = vec![];
+    'rounds: for d in 0..=max {
+        if d > MAX_EDITS {
+            return vec![];
+        }
+        trace.push(v[at(-d)..=at(d)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let mut x = match k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                true => v[at(k + 1)],
+                false => v[at(k - 1)] + 1,
+            };
+            let mut y = x - k;
+            while x < n as isize && y < m as isize && same(x as usize, y as usize) {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n as isize && y >= m as isize {
+                break 'rounds;
+            }
+        }
+    }
+    let mut pairs = vec![];
+    let (mut x, mut y) = (n as isize, m as isize);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let get = |k: isize| v[(k + d) as usize];
+        let k = x - y;
+        let prev_k = match k == -d || (k != d && get(k - 1) < get(k + 1)) {
+            true => k + 1,
+            false => k - 1,
+        };
+        let prev_x = match d {
+            0 => 0,
+            _ => get(prev_k),
+        };
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            pairs.push((x as usize, y as usize));
+        }
+        (x, y) = (prev_x, prev_y);
+    }
+    pairs.reverse();
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lexicon;
+
+    // one lexicon of space separated words
+    fn parse(text: &str, using_keys: bool) -> Graph {
+        let mut graph = Graph::new(String::new(), using_keys);
+        let mut lexicon = Lexicon::new(0);
+        for word in text.split(' ') {
+            let mut lexeme = graph.push_word(word, None);
+            lexeme.set_suffix(graph.push_str(" "));
+            lexicon.push_lexeme(lexeme);
+        }
+        graph.lexicons.push(lexicon);
+        graph
+    }
+
+    #[test]
+    fn it_works() {
+        let old = parse("Saya suka makan nasi goreng", false);
+        let new = parse("Saya sangat suka makan mie goreng enak", false);
+        let changes: Vec<(Operation, usize, usize, String, String)> = old
+            .diff(&new)
+            .into_iter()
+            .map(|c| {
+                (
+                    c.operation,
+                    c.old_offset,
+                    c.new_offset,
+                    c.old_text,
+                    c.new_text,
+                )
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (Operation::Insert, 5, 5, "".to_owned(), "sangat".to_owned()),
+                (
+                    Operation::Replace,
+                    16,
+                    23,
+                    "nasi".to_owned(),
+                    "mie".to_owned()
+                ),
+                (Operation::Insert, 28, 34, "".to_owned(), "enak".to_owned()),
+            ]
+        );
+        assert_eq!(old.diff(&old), vec![]);
+        let changes = new.diff(&old);
+        assert_eq!(changes[0].operation, Operation::Delete);
+        assert_eq!(changes[0].new_length, 0);
+
+        // a word which kept its key is replaced on its own
+        let old = parse("saya makan nasi", true);
+        let mut new = Graph::new(String::new(), true);
+        let mut lexicon = Lexicon::new(0);
+        for lexeme in old.lexicons[0].lexemes.iter() {
+            let word = match old.get_word(lexeme) {
+                "makan" => "memakan",
+                word => word,
+            };
+            let mut l = new.push_word(word, old.inherit_key(lexeme, word));
+            l.set_suffix(new.push_str(" "));
+            lexicon.push_lexeme(l);
+        }
+        new.lexicons.push(lexicon);
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].operation, Operation::Replace);
+        assert_eq!(changes[0].new_text, "memakan");
+        assert_eq!(changes[0].old_keys, changes[0].new_keys);
+
+        // too many edits give up on aligning, the texts are replaced whole
+        let words = |prefix: &str| {
+            (0..5000)
+                .map(|i| format!("{}{}", prefix, i))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let old = parse(&words("lama"), false);
+        let new = parse(&words("baru"), false);
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].operation, Operation::Replace);
+        assert_eq!(changes[0].old_text, words("lama"));
+        assert_eq!(changes[0].new_text, words("baru"));
+    }
+}
@@ -1,7 +1,14 @@
+mod casing;
+mod diff;
 mod types;
+pub use casing::Casing;
+pub use diff::{Change, Operation};
 use rand::Rng;
 use trim_in_place::TrimInPlace;
-pub use types::{Graph, Lexeme, LexemeMetadata, Lexicon, PosTagging};
+pub use types::{
+    Graph, Lexeme, LexemeMetadata, Lexicon, Morphology, PosTagging, Reduplication, Restoration,
+    Sentence, TokenKind,
+};
 
 impl Graph {
     pub fn new(text: String, using_keys: bool) -> Graph {
@@ -9,6 +16,7 @@ impl Graph {
             lexicons: vec![],
             text,
             using_keys,
+            sentences: vec![],
         }
     }
 
@@ -39,6 +47,16 @@ impl Graph {
         }
     }
 
+    /// Like `get_key`, but passes the key on to a word written differently,
+    /// e.g. when a lexeme is split or merged with its neighbour.
+    pub fn inherit_key<'a>(&self, lex: &Lexeme, word: &'a str) -> Option<(&'a str, usize)> {
+        if self.using_keys {
+            Some((word, lex.metadata.key))
+        } else {
+            None
+        }
+    }
+
     pub fn push_str(&mut self, text: &str) -> usize {
         self.text.push_str(text);
         self.text.len()
@@ -172,6 +190,26 @@ impl LexemeMetadata {
         LexemeMetadata {
             key: 0,
             pos: PosTagging::Unset,
+            kind: TokenKind::Word,
+            reduplication: None,
+            morphology: None,
+            unknown: false,
+            language: None,
         }
     }
+
+    /// Tagged by language identification as anything but Indonesian.
+    pub fn is_foreign(&self) -> bool {
+        self.language.as_deref().is_some_and(|l| l != "id")
+    }
+}
+
+impl Morphology {
+    /// The word split into its parts, e.g. "mem-per-baru-i".
+    pub fn segments(&self) -> String {
+        let mut parts: Vec<&str> = self.prefixes.iter().map(|s| s.as_str()).collect();
+        parts.push(&self.root);
+        parts.extend(self.suffixes.iter().map(|s| s.as_str()));
+        parts.join("-")
+    }
 }
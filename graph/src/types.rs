@@ -1,13 +1,15 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Graph {
     pub text: String,
     pub lexicons: Vec<Lexicon>,
     pub using_keys: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sentences: Vec<Sentence>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Lexicon {
     pub lexemes: Vec<Lexeme>,
     pub offset: usize,
@@ -17,30 +19,146 @@ pub struct Lexicon {
     pub suffix: usize,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// A group of consecutive lexicons forming one sentence.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Sentence {
+    /// byte offset into `Graph.text`
+    pub offset: usize,
+    pub length: usize,
+    /// index of the first lexicon
+    pub lexicon: usize,
+    /// number of lexicons
+    pub lexicons: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Lexeme {
     pub offset: usize,
     pub length: usize,
     pub suffix: usize,
-    #[serde(skip_serializing_if = "metadata_is_empty")]
+    #[serde(default = "LexemeMetadata::default", skip_serializing_if = "metadata_is_empty")]
     pub metadata: LexemeMetadata,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LexemeMetadata {
+    #[serde(default)]
     pub key: usize,
-    #[serde(skip_serializing_if = "pos_is_empty")]
+    #[serde(default, skip_serializing_if = "pos_is_empty")]
     pub pos: PosTagging,
+    #[serde(default, skip_serializing_if = "kind_is_word")]
+    pub kind: TokenKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reduplication: Option<Reduplication>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub morphology: Option<Morphology>,
+    /// set when stemming found no root for the word
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub unknown: bool,
+    /// ISO 639-1 code set by language identification, e.g. "id" or "en"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// How a word is built from its root (mem-per-baru-i)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Morphology {
+    pub root: String,
+    /// outermost first
+    pub prefixes: Vec<String>,
+    /// innermost first
+    pub suffixes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restoration: Option<Restoration>,
+    /// other roots which also matched the word
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<String>,
+}
+
+/// The root letter a nasal prefix replaced (meny -> s in menyapu)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Restoration {
+    pub prefix: String,
+    pub restored: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
 pub enum PosTagging {
+    #[default]
     Unset,
-    Unknown
+    Unknown,
+    Noun,
+    Verb,
+    Adj,
+    Adv,
+    Pron,
+    Num,
+    Propn,
+    Adp,
+    Conj,
+    Part,
+    Det,
+    Intj,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum TokenKind {
+    #[default]
+    Word,
+    /// 1.500.000 or 3,14
+    Number,
+    /// Rp 25.000,50
+    Currency,
+    /// 12/08/2024
+    Date,
+    /// 14.30 WIB
+    Time,
+    /// 50%
+    Percentage,
+    /// ke-3
+    Ordinal,
+    /// https://benerin.web.id
+    Url,
+    /// halo@benerin.web.id
+    Email,
+    /// 0812-3456-7890 or +62 812 3456 7890
+    Phone,
+    /// @benerin
+    Mention,
+    /// #benerin
+    Hashtag,
+    Emoji,
+}
+
+/// Kinds of reduplicated words (kata ulang)
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Reduplication {
+    /// buku-buku
+    Full,
+    /// lelaki, tetamu
+    Partial,
+    /// sayur-mayur, bolak-balik
+    Rhythmic,
+    /// berlari-lari, anak-anaknya
+    Affixed,
 }
 
 fn metadata_is_empty(metadata: &LexemeMetadata) -> bool {
     metadata.key == 0
+        && pos_is_empty(&metadata.pos)
+        && kind_is_word(&metadata.kind)
+        && metadata.reduplication.is_none()
+        && metadata.morphology.is_none()
+        && !metadata.unknown
+        && metadata.language.is_none()
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn kind_is_word(kind: &TokenKind) -> bool {
+    *kind == TokenKind::Word
 }
 
 fn pos_is_empty(metadata: &PosTagging) -> bool {
@@ -0,0 +1,20 @@
+// Rebuilds the shipped profiles from plain text corpora, one file per language:
+// cargo run -p langid --example train -- id=id.txt en=en.txt > langid/src/profiles.tsv
+
+use std::{env, fs};
+
+fn main() {
+    let corpora: Vec<(String, String)> = env::args()
+        .skip(1)
+        .filter_map(|arg| {
+            let (language, path) = arg.split_once('=')?;
+            let corpus = fs::read_to_string(path).expect("cannot read corpus");
+            Some((language.to_owned(), corpus))
+        })
+        .collect();
+    let corpora: Vec<(&str, &str)> = corpora
+        .iter()
+        .map(|(language, corpus)| (language.as_str(), corpus.as_str()))
+        .collect();
+    print!("{}", langid::train(&corpora));
+}
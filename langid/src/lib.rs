@@ -0,0 +1,257 @@
+// Language identification with character n-grams. Each language has a table
+// of n-gram counts trained offline (see examples/train.rs), a word is scored
+// with naive bayes and the document language is a vote of its words.
+
+use std::collections::{HashMap, HashSet};
+
+use graph::{Graph, TokenKind};
+use tokenizer::Tokenizer;
+
+// single letters say more about the corpus size than the language
+const MIN_NGRAM: usize = 2;
+const MAX_NGRAM: usize = 4;
+const SMOOTHING: f32 = 0.5;
+// shorter words take the document language, "di" or "a" say too little
+const MIN_LETTERS: usize = 3;
+// average log-probability gap per n-gram for a word to keep its own language
+const MIN_MARGIN: f32 = 0.35;
+
+struct Profile {
+    language: String,
+    counts: HashMap<String, u32>,
+    total: u32,
+}
+
+pub struct Langid {
+    tokenizer: Tokenizer,
+    profiles: Vec<Profile>,
+    vocabulary: usize,
+}
+
+impl Default for Langid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Langid {
+    // Initialization function
+    pub fn new() -> Self {
+        Langid::from_profiles(include_str!("profiles.tsv"))
+    }
+
+    /// Loads tab separated `language`, `ngram` and `count` lines.
+    pub fn from_profiles(data: &str) -> Self {
+        let mut profiles: Vec<Profile> = vec![];
+        let mut vocabulary = HashSet::new();
+        for line in data.lines() {
+            let mut fields = line.split('\t');
+            let (Some(language), Some(ngram), Some(count)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let Ok(count) = count.parse::<u32>() else {
+                continue;
+            };
+            let i = match profiles.iter().position(|p| p.language == language) {
+                Some(i) => i,
+                None => {
+                    profiles.push(Profile {
+                        language: language.to_owned(),
+                        counts: HashMap::new(),
+                        total: 0,
+                    });
+                    profiles.len() - 1
+                }
+            };
+            profiles[i].counts.insert(ngram.to_owned(), count);
+            profiles[i].total += count;
+            vocabulary.insert(ngram.to_owned());
+        }
+        Langid {
+            tokenizer: Tokenizer::new(),
+            profiles,
+            vocabulary: vocabulary.len(),
+        }
+    }
+
+    pub fn languages(&self) -> Vec<&str> {
+        self.profiles.iter().map(|p| p.language.as_str()).collect()
+    }
+
+    /// Language of a text, None when it has no words.
+    pub fn detect(&self, text: &str) -> Option<&str> {
+        let graph = self.tokenizer.parse(text.to_owned());
+        let words: Vec<&str> = words(&graph).map(|(_, _, w)| w).collect();
+        self.detect_words(&words)
+    }
+
+    /// Language of a single word along with its margin over the runner-up.
+    pub fn detect_word(&self, word: &str) -> Option<(&str, f32)> {
+        let grams = ngrams(&word.to_lowercase());
+        if grams.is_empty() || self.profiles.is_empty() {
+            return None;
+        }
+        let mut scores = self.score(&grams);
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let margin = match scores.get(1) {
+            Some(second) => (scores[0].1 - second.1) / grams.len() as f32,
+            None => f32::INFINITY,
+        };
+        Some((scores[0].0, margin))
+    }
+
+    /// Sets `metadata.language` of every word and returns the document language.
+    pub fn tag_graph(&self, graph: &mut Graph) -> Option<String> {
+        let words: Vec<(usize, usize, &str)> = words(graph).collect();
+        let document = self
+            .detect_words(&words.iter().map(|(_, _, w)| *w).collect::<Vec<_>>())?
+            .to_owned();
+        let mut languages = vec![];
+        for (i, j, word) in words {
+            let language = match self.detect_word(word) {
+                Some((language, margin))
+                    if word.chars().count() >= MIN_LETTERS && margin >= MIN_MARGIN =>
+                {
+                    language
+                }
+                _ => &document,
+            };
+            languages.push((i, j, language.to_owned()));
+        }
+        for (i, j, language) in languages {
+            graph.lexicons[i].lexemes[j].metadata.language = Some(language);
+        }
+        Some(document)
+    }
+
+    // every word votes for its language with its margin, so a few long
+    // foreign words don't outweigh many short native ones
+    fn detect_words(&self, words: &[&str]) -> Option<&str> {
+        let mut votes: Vec<(&str, f32)> = vec![];
+        for (language, margin) in words.iter().filter_map(|w| self.detect_word(w)) {
+            match votes.iter_mut().find(|v| v.0 == language) {
+                Some(vote) => vote.1 += margin,
+                None => votes.push((language, margin)),
+            }
+        }
+        votes
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(language, _)| language)
+    }
+
+    fn score(&self, grams: &[String]) -> Vec<(&str, f32)> {
+        self.profiles
+            .iter()
+            .map(|p| {
+                let denominator = p.total as f32 + SMOOTHING * self.vocabulary as f32;
+                let score = grams
+                    .iter()
+                    .map(|g| {
+                        let count = p.counts.get(g).copied().unwrap_or(0) as f32;
+                        ((count + SMOOTHING) / denominator).ln()
+                    })
+                    .sum();
+                (p.language.as_str(), score)
+            })
+            .collect()
+    }
+}
+
+/// Builds a profile for `from_profiles` out of `(language, corpus)` pairs.
+pub fn train(corpora: &[(&str, &str)]) -> String {
+    let mut lines = vec![];
+    for (language, corpus) in corpora {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        let lower = corpus.to_lowercase();
+        for word in lower.split(|c: char| !c.is_alphabetic()) {
+            for gram in ngrams(word) {
+                *counts.entry(gram).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<(String, u32)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        for (gram, count) in counts {
+            lines.push(format!("{}\t{}\t{}\n", language, gram, count));
+        }
+    }
+    lines.concat()
+}
+
+// word lexemes with their lexicon and lexeme index
+fn words(graph: &Graph) -> impl Iterator<Item = (usize, usize, &str)> {
+    graph
+        .lexicons
+        .iter()
+        .enumerate()
+        .flat_map(move |(i, lexicon)| {
+            lexicon
+                .lexemes
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| l.metadata.kind == TokenKind::Word)
+                .map(move |(j, l)| (i, j, graph.get_word(l)))
+        })
+}
+
+// n-grams of the word padded with spaces, "kita" gives " k", "ki", ..., "ita " and " kita "
+fn ngrams(word: &str) -> Vec<String> {
+    let chars: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+    if chars.is_empty() {
+        return vec![];
+    }
+    let padded: Vec<char> = [&[' '], chars.as_slice(), &[' ']].concat();
+    let mut grams = vec![];
+    for n in MIN_NGRAM..=MAX_NGRAM {
+        for window in padded.windows(n) {
+            grams.push(window.iter().collect());
+        }
+    }
+    // the whole word, so words seen in training weigh more than their parts
+    if padded.len() > MAX_NGRAM {
+        grams.push(padded.iter().collect());
+    }
+    grams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let langid = Langid::new();
+        assert_eq!(langid.languages(), vec!["id", "en"]);
+        assert_eq!(langid.detect("saya sedang makan nasi goreng"), Some("id"));
+        assert_eq!(
+            langid.detect("please update the meeting schedule"),
+            Some("en")
+        );
+        assert_eq!(langid.detect("123 !!"), None);
+        assert_eq!(langid.detect_word("pembangunan").map(|d| d.0), Some("id"));
+        assert_eq!(langid.detect_word("download").map(|d| d.0), Some("en"));
+
+        let tokenizer = Tokenizer::new();
+        let mut graph = tokenizer.parse("Tolong download file ini di laptop saya".to_owned());
+        assert_eq!(langid.tag_graph(&mut graph), Some("id".to_owned()));
+        let languages: Vec<_> = graph.lexicons[0]
+            .lexemes
+            .iter()
+            .map(|l| l.metadata.language.as_deref())
+            .collect();
+        assert_eq!(
+            languages,
+            vec![
+                Some("id"),
+                Some("en"),
+                Some("en"),
+                Some("id"),
+                Some("id"),
+                Some("id"),
+                Some("id")
+            ]
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+
+use graph::{Graph, TokenKind};
+use serde::Serialize;
+use spellcheck::symspell::{SymSpell, Verbosity};
+use stemmer::Frequency;
+use tokenizer::Tokenizer;
+
+mod spam;
+
+// separators which hide a word without really splitting it, "a.n.j.i.n.g"
+const SEPARATORS: &[char] = &['.', '-', '_'];
+
+// parts of a spelled out word are at most this long
+const SPELLED_PART: usize = 2;
+
+// leetspeak digits and symbols and the letters they stand for
+const LEET: &[(char, char)] = &[
+    ('4', 'a'),
+    ('@', 'a'),
+    ('3', 'e'),
+    ('1', 'i'),
+    ('!', 'i'),
+    ('0', 'o'),
+    ('5', 's'),
+    ('$', 's'),
+    ('7', 't'),
+    ('8', 'b'),
+    ('9', 'g'),
+];
+
+// listed words need a count above the SymSpell count threshold
+const WORD_COUNT: i32 = 100;
+
+pub struct Moderation {
+    tokenizer: Tokenizer,
+    words: HashMap<String, Category>,
+    // the same words, to match obfuscated or misspelled ones
+    symspell: SymSpell,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Profanity,
+    Insult,
+    Sexual,
+    /// more links than a comment needs
+    Links,
+    /// "mantaaaaap" or "promo promo promo"
+    Repetition,
+    /// several words in capitals
+    Shouting,
+}
+
+impl Category {
+    /// Profanity, insults and sexual words, the ones worth masking.
+    pub fn is_abusive(&self) -> bool {
+        matches!(
+            self,
+            Category::Profanity | Category::Insult | Category::Sexual
+        )
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct Flag {
+    pub category: Category,
+    /// byte offset into `Graph.text`
+    pub offset: usize,
+    pub length: usize,
+    pub text: String,
+    /// listed word an obfuscated or misspelled word stands for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word: Option<String>,
+}
+
+pub(crate) struct Token<'a> {
+    pub word: &'a str,
+    /// byte offset into `Graph.text`
+    pub offset: usize,
+    pub end: usize,
+    pub kind: TokenKind,
+}
+
+pub(crate) fn tokens(graph: &Graph) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    for lexicon in &graph.lexicons {
+        for lexeme in lexicon.lexemes.iter() {
+            tokens.push(Token {
+                word: graph.get_word(lexeme),
+                offset: lexeme.offset,
+                end: lexeme.offset + lexeme.length,
+                kind: lexeme.metadata.kind,
+            });
+        }
+    }
+    tokens
+}
+
+pub(crate) fn flag(graph: &Graph, category: Category, offset: usize, end: usize) -> Flag {
+    Flag {
+        category,
+        offset,
+        length: end - offset,
+        text: graph.text[offset..end].to_owned(),
+        word: None,
+    }
+}
+
+impl Default for Moderation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Moderation {
+    // Initialization function
+    pub fn new() -> Self {
+        let mut moderation = Moderation {
+            tokenizer: Tokenizer::new(),
+            words: HashMap::new(),
+            symspell: SymSpell::default(),
+        };
+        moderation.load_words(include_str!("words.tsv"));
+        moderation
+    }
+
+    /// Load tab separated categories (profanity, insult or sexual) and words,
+    /// one per line. Later entries override earlier ones.
+    pub fn load_words(&mut self, data: &str) {
+        for line in data.lines() {
+            let Some((category, word)) = line.split_once('\t') else {
+                continue;
+            };
+            let category = match category {
+                "profanity" => Category::Profanity,
+                "insult" => Category::Insult,
+                "sexual" => Category::Sexual,
+                _ => continue,
+            };
+            let word = word.trim().to_lowercase();
+            let entry = format!("{},{}", word, WORD_COUNT);
+            self.symspell.load_dictionary_line(&entry, 0, 1, ",");
+            self.words.insert(word, category);
+        }
+    }
+
+    pub fn check(&self, text: &str) -> Vec<Flag> {
+        let graph = self.tokenizer.parse(text.to_owned());
+        self.check_graph(&graph, None)
+    }
+
+    /// Flags abusive words and spam. Obfuscated words are compared with the
+    /// list by edit distance, so are words `frequency` does not know.
+    pub fn check_graph(&self, graph: &Graph, frequency: Option<&dyn Frequency>) -> Vec<Flag> {
+        let tokens = tokens(graph);
+        let mut flags = self.abusive_words(graph, &tokens, frequency);
+        flags.extend(spam::links(graph, &tokens));
+        flags.extend(spam::repetition(graph, &tokens));
+        flags.extend(spam::shouting(graph, &tokens));
+        flags.sort_by_key(|f| f.offset);
+        flags
+    }
+
+    /// Renders the text with its abusive words masked, "b******".
+    pub fn mask(&self, text: &str) -> String {
+        let graph = self.tokenizer.parse(text.to_owned());
+        let spans: Vec<(usize, usize)> = self
+            .check_graph(&graph, None)
+            .iter()
+            .filter(|f| f.category.is_abusive())
+            .map(|f| (f.offset, f.length))
+            .collect();
+        self.tokenizer.render_masked(&graph, &spans)
+    }
+
+    fn abusive_words(
+        &self,
+        graph: &Graph,
+        tokens: &[Token],
+        frequency: Option<&dyn Frequency>,
+    ) -> Vec<Flag> {
+        let mut flags = vec![];
+        let mut i = 0;
+        while i < tokens.len() {
+            if !matches!(
+                tokens[i].kind,
+                TokenKind::Word | TokenKind::Mention | TokenKind::Hashtag
+            ) {
+                i += 1;
+                continue;
+            }
+            // "a.n.j.i.n.g" is parsed as single letters
+            let end = spelled_end(graph, tokens, i);
+            let found = match end > i + 1 {
+                true => self
+                    .lookup(
+                        &graph.text[tokens[i].offset..tokens[end - 1].end],
+                        frequency,
+                    )
+                    .map(|found| (found, end)),
+                false => None,
+            };
+            let found = found.or_else(|| {
+                self.lookup(tokens[i].word, frequency)
+                    .map(|found| (found, i + 1))
+            });
+            let Some(((word, category), end)) = found else {
+                i += 1;
+                continue;
+            };
+            let mut flag = flag(graph, category, tokens[i].offset, tokens[end - 1].end);
+            if flag.text.to_lowercase() != word {
+                flag.word = Some(word.to_owned());
+            }
+            flags.push(flag);
+            i = end;
+        }
+        flags
+    }
+
+    // the listed word an obfuscated, stretched or misspelled word stands for
+    fn lookup(&self, word: &str, frequency: Option<&dyn Frequency>) -> Option<(&str, Category)> {
+        let lower = word.to_lowercase();
+        let plain = unleet(&lower);
+        let squeezed = squeeze(&plain);
+        for candidate in [&plain, &squeezed] {
+            if let Some((word, category)) = self.words.get_key_value(candidate) {
+                return Some((word, *category));
+            }
+        }
+        let obfuscated = plain != lower || squeezed != plain || plain.contains('*');
+        let known = frequency.map(|f| f.frequency(&plain) > 0 || f.frequency(&squeezed) > 0);
+        match known {
+            Some(true) => return None,
+            None if !obfuscated => return None,
+            _ => {}
+        }
+        // "f**k" has two letters hidden
+        let hidden = squeezed.matches('*').count() as i32;
+        let max_distance = match squeezed.chars().count() {
+            0..=3 => 0,
+            4..=7 => 1,
+            _ => 2,
+        };
+        let max_distance = max_distance.max(hidden).min(2);
+        if max_distance == 0 {
+            return None;
+        }
+        // misspellings seldom change the first letter
+        let first = squeezed.chars().next()?;
+        self.symspell
+            .lookup(&squeezed, Verbosity::Top, max_distance)
+            .into_iter()
+            .find(|s| first == '*' || s.term.starts_with(first))
+            .and_then(|s| self.words.get_key_value(s.term.as_str()))
+            .map(|(word, category)| (word.as_str(), *category))
+    }
+}
+
+// end index (exclusive) of the one or two letter parts from `start` on,
+// joined by separators only
+fn spelled_end(graph: &Graph, tokens: &[Token], start: usize) -> usize {
+    let is_part = |t: &Token| t.kind == TokenKind::Word && t.word.chars().count() <= SPELLED_PART;
+    let mut end = start + 1;
+    while end < tokens.len() && is_part(&tokens[end - 1]) && is_part(&tokens[end]) {
+        let gap = &graph.text[tokens[end - 1].end..tokens[end].offset];
+        if gap.is_empty() || !gap.chars().all(|c| SEPARATORS.contains(&c) || c == '*') {
+            break;
+        }
+        end += 1;
+    }
+    end
+}
+
+// "B4ngs4t" becomes "bangsat", separators and hashtags are left out
+fn unleet(lower: &str) -> String {
+    lower
+        .chars()
+        .filter(|c| !SEPARATORS.contains(c) && *c != '#')
+        .map(|c| LEET.iter().find(|(l, _)| *l == c).map_or(c, |(_, r)| *r))
+        .collect()
+}
+
+// "bangsaaat" becomes "bangsat", hidden letters are kept apart
+fn squeeze(word: &str) -> String {
+    let mut squeezed = String::new();
+    for c in word.chars() {
+        if c == '*' || !squeezed.ends_with(c) {
+            squeezed.push(c);
+        }
+    }
+    squeezed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let moderation = Moderation::new();
+        let found = |text: &str| -> Vec<(Category, String, Option<String>)> {
+            moderation
+                .check(text)
+                .into_iter()
+                .map(|f| (f.category, f.text, f.word))
+                .collect()
+        };
+        assert_eq!(
+            found("Dasar g0bl0k, b4ngs4t kamu."),
+            vec![
+                (
+                    Category::Insult,
+                    "g0bl0k".to_owned(),
+                    Some("goblok".to_owned())
+                ),
+                (
+                    Category::Profanity,
+                    "b4ngs4t".to_owned(),
+                    Some("bangsat".to_owned())
+                ),
+            ]
+        );
+        assert_eq!(
+            found("a.n.j.i.n.g"),
+            vec![(
+                Category::Profanity,
+                "a.n.j.i.n.g".to_owned(),
+                Some("anjing".to_owned())
+            )]
+        );
+        assert_eq!(found("bangsaaat")[0].2, Some("bangsat".to_owned()));
+        assert_eq!(found("anjiinkk")[0].2, Some("anjing".to_owned()));
+        assert_eq!(found("Dasar goblok")[0].2, None);
+        assert_eq!(found("Bangsa dan negara, satu nusa."), vec![]);
+        assert_eq!(moderation.mask("Dasar g0bl0k!"), "Dasar g*****!");
+    }
+}
@@ -0,0 +1,132 @@
+// Comments which look like spam rather than abuse: link dumps, stretched
+// letters, repeated words and shouting.
+
+use graph::{Graph, TokenKind};
+
+use crate::{flag, Category, Flag, Token};
+
+// more links than this are flagged
+const MAX_LINKS: usize = 2;
+
+// "mantaaaaap" or "!!!!!"
+const MIN_REPEATED_CHARS: usize = 5;
+
+// "promo promo promo"
+const MIN_REPEATED_WORDS: usize = 3;
+
+// "BELI SEKARANG JUGA GAN"
+const MIN_SHOUTING_WORDS: usize = 4;
+
+pub fn links(graph: &Graph, tokens: &[Token]) -> Vec<Flag> {
+    let links: Vec<&Token> = tokens.iter().filter(|t| t.kind == TokenKind::Url).collect();
+    if links.len() <= MAX_LINKS {
+        return vec![];
+    }
+    links
+        .into_iter()
+        .map(|t| flag(graph, Category::Links, t.offset, t.end))
+        .collect()
+}
+
+pub fn repetition(graph: &Graph, tokens: &[Token]) -> Vec<Flag> {
+    let mut flags: Vec<Flag> = vec![];
+    // the same letter or symbol over and over, flagged along with its word
+    let mut chars = graph.text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let mut end = start + c.len_utf8();
+        let mut count = 1;
+        while let Some((i, _)) = chars.next_if(|(_, next)| *next == c) {
+            end = i + c.len_utf8();
+            count += 1;
+        }
+        if count < MIN_REPEATED_CHARS || c.is_whitespace() || c.is_ascii_digit() {
+            continue;
+        }
+        let (start, end) = match tokens.iter().find(|t| t.offset <= start && start < t.end) {
+            Some(t) => (t.offset, t.end.max(end)),
+            None => (start, end),
+        };
+        if flags.last().is_some_and(|f| f.offset == start) {
+            continue;
+        }
+        flags.push(flag(graph, Category::Repetition, start, end));
+    }
+    let mut i = 0;
+    while i < tokens.len() {
+        let word = tokens[i].word.to_lowercase();
+        let mut end = i + 1;
+        while end < tokens.len()
+            && tokens[end].kind == TokenKind::Word
+            && tokens[end].word.to_lowercase() == word
+        {
+            end += 1;
+        }
+        if tokens[i].kind == TokenKind::Word && end - i >= MIN_REPEATED_WORDS {
+            flags.push(flag(
+                graph,
+                Category::Repetition,
+                tokens[i].offset,
+                tokens[end - 1].end,
+            ));
+        }
+        i = end;
+    }
+    flags
+}
+
+pub fn shouting(graph: &Graph, tokens: &[Token]) -> Vec<Flag> {
+    // a single letter or "PT" alone is not shouting
+    let is_shouted = |t: &Token| {
+        t.kind == TokenKind::Word
+            && t.word.chars().filter(|c| c.is_alphabetic()).count() >= 2
+            && !t.word.chars().any(|c| c.is_lowercase())
+    };
+    let mut flags = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        let mut end = i;
+        while end < tokens.len() && is_shouted(&tokens[end]) {
+            end += 1;
+        }
+        if end - i >= MIN_SHOUTING_WORDS {
+            flags.push(flag(
+                graph,
+                Category::Shouting,
+                tokens[i].offset,
+                tokens[end - 1].end,
+            ));
+        }
+        i = end.max(i + 1);
+    }
+    flags
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Category, Moderation};
+
+    #[test]
+    fn it_works() {
+        let moderation = Moderation::new();
+        let found = |text: &str| -> Vec<(Category, String)> {
+            moderation
+                .check(text)
+                .into_iter()
+                .map(|f| (f.category, f.text))
+                .collect()
+        };
+        assert_eq!(
+            found("PROMO MURAH BANGET HARI INI!!!!! Mantaaaaap"),
+            vec![
+                (Category::Shouting, "PROMO MURAH BANGET HARI INI".to_owned()),
+                (Category::Repetition, "!!!!!".to_owned()),
+                (Category::Repetition, "Mantaaaaap".to_owned()),
+            ]
+        );
+        assert_eq!(
+            found("cek https://a.id https://b.id https://c.id promo promo promo").len(),
+            4
+        );
+        assert_eq!(found("Harganya Rp 1.000.000 di PT ABC."), vec![]);
+    }
+}
@@ -0,0 +1,127 @@
+// Known names of places, organisations and people. Multi-word names are
+// matched longest first, a matched first name takes the surnames after it.
+
+use std::collections::HashMap;
+
+use graph::Graph;
+use tokenizer::Tokenizer;
+
+use crate::token::{capitalized_run, Token};
+use crate::{entity, Entity, Label, Recognizer};
+
+// "Jakarta Selatan" or "Sumatera Utara" without listing every one of them
+const DIRECTIONS: &[&str] = &["utara", "selatan", "barat", "timur", "tengah", "pusat"];
+
+pub struct Gazetteer {
+    tokenizer: Tokenizer,
+    names: HashMap<String, Label>,
+    // most words in a single name
+    max_words: usize,
+}
+
+impl Default for Gazetteer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Gazetteer {
+    // Initialization function
+    pub fn new() -> Self {
+        let mut gazetteer = Gazetteer {
+            tokenizer: Tokenizer::new(),
+            names: HashMap::new(),
+            max_words: 1,
+        };
+        gazetteer.load(include_str!("gazetteer.tsv"));
+        gazetteer
+    }
+
+    /// Load tab separated labels (PERSON, ORG or LOC) and names, one per line.
+    /// Later entries override earlier ones.
+    pub fn load(&mut self, data: &str) {
+        for line in data.lines() {
+            let Some((label, name)) = line.split_once('\t') else {
+                continue;
+            };
+            let label = match label {
+                "PERSON" => Label::Person,
+                "ORG" => Label::Org,
+                "LOC" => Label::Loc,
+                _ => continue,
+            };
+            let name = name.trim().to_lowercase();
+            self.max_words = self.max_words.max(name.split(' ').count());
+            self.names.insert(name, label);
+        }
+    }
+
+    // longest known name starting at `start`, along with its end
+    fn lookup(&self, tokens: &[Token], start: usize) -> Option<(Label, usize)> {
+        let mut name = String::new();
+        let mut found = None;
+        for end in start + 1..=tokens.len().min(start + self.max_words) {
+            let token = &tokens[end - 1];
+            if !token.is_word() || (end > start + 1 && !token.is_spaced()) {
+                break;
+            }
+            if end > start + 1 {
+                name.push(' ');
+            }
+            name.push_str(&token.lower());
+            if let Some(label) = self.names.get(&name) {
+                found = Some((*label, end));
+            }
+        }
+        found
+    }
+}
+
+impl Recognizer for Gazetteer {
+    fn recognize(&self, graph: &Graph) -> Vec<Entity> {
+        let sentences = match graph.sentences.is_empty() {
+            true => self.tokenizer.find_sentences(graph),
+            false => graph.sentences.clone(),
+        };
+        let tokens = Token::collect(graph, &sentences);
+        let mut entities = vec![];
+        let mut i = 0;
+        while i < tokens.len() {
+            let found = match tokens[i].is_capitalized() {
+                true => self.lookup(&tokens, i),
+                false => None,
+            };
+            let Some((label, mut end)) = found else {
+                i += 1;
+                continue;
+            };
+            match label {
+                Label::Person => end = capitalized_run(&tokens, i).max(end),
+                Label::Loc => {
+                    let direction = tokens.get(end).is_some_and(|t| {
+                        t.is_capitalized()
+                            && t.is_spaced()
+                            && DIRECTIONS.contains(&t.lower().as_str())
+                    });
+                    if direction {
+                        end += 1;
+                    }
+                }
+                _ => {}
+            }
+            // "Indah sekali" only starts a sentence, a lone first name is not enough
+            if label == Label::Person && end == i + 1 && tokens[i].sentence_start {
+                i += 1;
+                continue;
+            }
+            entities.push(entity(
+                graph,
+                label,
+                tokens[i].offset,
+                tokens[end - 1].end(),
+            ));
+            i = end;
+        }
+        entities
+    }
+}
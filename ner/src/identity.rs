@@ -0,0 +1,305 @@
+// Indonesian phone, NIK (KTP) and NPWP numbers. Each is normalized to one
+// format and checked against the structure the number is issued with.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use graph::{Graph, TokenKind};
+use serde::Serialize;
+
+use crate::token::Token;
+
+// BPS province codes, the first two digits of a NIK
+#[rustfmt::skip]
+const PROVINCES: &[(&str, &str)] = &[
+    ("11", "Aceh"), ("12", "Sumatera Utara"), ("13", "Sumatera Barat"), ("14", "Riau"),
+    ("15", "Jambi"), ("16", "Sumatera Selatan"), ("17", "Bengkulu"), ("18", "Lampung"),
+    ("19", "Kepulauan Bangka Belitung"), ("21", "Kepulauan Riau"), ("31", "DKI Jakarta"),
+    ("32", "Jawa Barat"), ("33", "Jawa Tengah"), ("34", "DI Yogyakarta"), ("35", "Jawa Timur"),
+    ("36", "Banten"), ("51", "Bali"), ("52", "Nusa Tenggara Barat"),
+    ("53", "Nusa Tenggara Timur"), ("61", "Kalimantan Barat"), ("62", "Kalimantan Tengah"),
+    ("63", "Kalimantan Selatan"), ("64", "Kalimantan Timur"), ("65", "Kalimantan Utara"),
+    ("71", "Sulawesi Utara"), ("72", "Sulawesi Tengah"), ("73", "Sulawesi Selatan"),
+    ("74", "Sulawesi Tenggara"), ("75", "Gorontalo"), ("76", "Sulawesi Barat"),
+    ("81", "Maluku"), ("82", "Maluku Utara"), ("91", "Papua"), ("92", "Papua Barat"),
+    ("93", "Papua Selatan"), ("94", "Papua Tengah"), ("95", "Papua Pegunungan"),
+    ("96", "Papua Barat Daya"),
+];
+
+// regency codes from 71 on are cities (kota)
+const FIRST_CITY: u32 = 71;
+
+// women have 40 added to the day of birth
+const FEMALE_DAY: u32 = 40;
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum IdentityKind {
+    Phone,
+    /// Nomor Induk Kependudukan, printed on the KTP
+    Nik,
+    /// Nomor Pokok Wajib Pajak
+    Npwp,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct Identity {
+    pub kind: IdentityKind,
+    /// byte offset into `Graph.text`
+    pub offset: usize,
+    pub length: usize,
+    pub text: String,
+    /// +6281234567890, 3201234567890001 or 01.234.567.8-901.000
+    pub normalized: String,
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nik: Option<NikDetails>,
+}
+
+/// What a NIK tells about its owner.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct NikDetails {
+    pub province_code: String,
+    pub province: &'static str,
+    /// province and regency, e.g. 3201
+    pub regency_code: String,
+    /// kota rather than kabupaten
+    pub city: bool,
+    /// province, regency and district, e.g. 320123
+    pub district_code: String,
+    /// YYYY-MM-DD
+    pub birth_date: String,
+    pub female: bool,
+}
+
+/// Phone, NIK and NPWP numbers in the graph, in text order.
+pub fn extract(graph: &Graph) -> Vec<Identity> {
+    let tokens = Token::collect(graph, &[]);
+    let mut found = vec![];
+    let mut next = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        if token.offset < next {
+            continue;
+        }
+        let identity = match token.kind {
+            TokenKind::Phone => Some(phone(graph, token.offset, token.end())),
+            TokenKind::Number => document(graph, &tokens, i),
+            _ => None,
+        };
+        if let Some(identity) = identity {
+            next = identity.offset + identity.length;
+            found.push(identity);
+        }
+    }
+    found
+}
+
+fn identity(graph: &Graph, kind: IdentityKind, offset: usize, end: usize) -> Identity {
+    Identity {
+        kind,
+        offset,
+        length: end - offset,
+        text: graph.text[offset..end].to_owned(),
+        normalized: String::new(),
+        valid: false,
+        nik: None,
+    }
+}
+
+fn phone(graph: &Graph, offset: usize, end: usize) -> Identity {
+    let mut phone = identity(graph, IdentityKind::Phone, offset, end);
+    let digits: String = phone.text.chars().filter(char::is_ascii_digit).collect();
+    let local = match digits.strip_prefix("62") {
+        Some(local) => local,
+        // other countries are taken as they are
+        None if phone.text.starts_with('+') => {
+            phone.normalized = format!("+{}", digits);
+            phone.valid = true;
+            return phone;
+        }
+        None => digits.strip_prefix('0').unwrap_or(&digits),
+    };
+    phone.normalized = format!("+62{}", local);
+    // mobile numbers start with 8, landlines with a 2 or 3 digit area code
+    phone.valid = match local.starts_with('8') {
+        true => (9..=12).contains(&local.len()),
+        false => (8..=10).contains(&local.len()),
+    };
+    phone
+}
+
+// digits with single '.', '-' or ' ' between them, ended where 15 or 16
+// digits have been read
+fn document_len(text: &str) -> Option<(usize, String)> {
+    let b = text.as_bytes();
+    let mut digits = String::new();
+    let mut found = None;
+    let mut i = 0;
+    while i < b.len() && digits.len() < 16 {
+        if b[i].is_ascii_digit() {
+            digits.push(b[i] as char);
+            i += 1;
+            if digits.len() >= 15 && !b.get(i).is_some_and(u8::is_ascii_digit) {
+                found = Some((i, digits.clone()));
+            }
+        } else if matches!(b[i], b'.' | b'-' | b' ') && b.get(i + 1).is_some_and(u8::is_ascii_digit)
+        {
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    found
+}
+
+fn document(graph: &Graph, tokens: &[Token], i: usize) -> Option<Identity> {
+    let offset = tokens[i].offset;
+    let (len, digits) = document_len(&graph.text[offset..])?;
+    let text = &graph.text[offset..offset + len];
+    // 1.500.000.000.000.000 is an amount
+    if text.contains('.') && !text.contains('-') {
+        return None;
+    }
+    // the new 16 digit NPWP looks like a NIK unless it is called one
+    let called_npwp = tokens[i.saturating_sub(2)..i]
+        .iter()
+        .any(|t| t.lower() == "npwp");
+    let kind = match digits.len() == 15 || called_npwp {
+        true => IdentityKind::Npwp,
+        false => IdentityKind::Nik,
+    };
+    let mut found = identity(graph, kind, offset, offset + len);
+    match kind {
+        IdentityKind::Nik => {
+            found.nik = decode_nik(&digits);
+            found.valid = found.nik.is_some();
+            found.normalized = digits;
+        }
+        _ if digits.len() == 15 => {
+            found.valid = valid_npwp(&digits);
+            found.normalized = format!(
+                "{}.{}.{}.{}-{}.{}",
+                &digits[..2],
+                &digits[2..5],
+                &digits[5..8],
+                &digits[8..9],
+                &digits[9..12],
+                &digits[12..]
+            );
+        }
+        // individuals use their NIK, others a zero before the old NPWP
+        _ => {
+            found.valid = match digits.strip_prefix('0') {
+                Some(old) => valid_npwp(old),
+                None => decode_nik(&digits).is_some(),
+            };
+            found.normalized = digits;
+        }
+    }
+    Some(found)
+}
+
+// the taxpayer type and serial are never zero, the check digit is not published
+fn valid_npwp(digits: &str) -> bool {
+    digits.len() == 15 && &digits[..2] != "00" && &digits[2..8] != "000000"
+}
+
+fn decode_nik(digits: &str) -> Option<NikDetails> {
+    if digits.len() != 16 || &digits[12..] == "0000" {
+        return None;
+    }
+    let (_, province) = PROVINCES.iter().find(|(code, _)| *code == &digits[..2])?;
+    let regency: u32 = digits[2..4].parse().ok()?;
+    if regency == 0 || &digits[4..6] == "00" {
+        return None;
+    }
+    let mut day: u32 = digits[6..8].parse().ok()?;
+    let month: u32 = digits[8..10].parse().ok()?;
+    let year: u32 = digits[10..12].parse().ok()?;
+    let female = day > FEMALE_DAY;
+    if female {
+        day -= FEMALE_DAY;
+    }
+    // only two digits of the year are kept, pick the century which is not ahead
+    let year = match 2000 + year > current_year() {
+        true => 1900 + year,
+        false => 2000 + year,
+    };
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(NikDetails {
+        province_code: digits[..2].to_owned(),
+        province,
+        regency_code: digits[..4].to_owned(),
+        city: regency >= FIRST_CITY,
+        district_code: digits[..6].to_owned(),
+        birth_date: format!("{:04}-{:02}-{:02}", year, month, day),
+        female,
+    })
+}
+
+fn current_year() -> u32 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    // average Gregorian year in seconds
+    1970 + (secs / 31_556_952) as u32
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokenizer::Tokenizer;
+
+    #[test]
+    fn it_works() {
+        let tokenizer = Tokenizer::new();
+        let found = |text: &str| extract(&tokenizer.parse(text.to_owned()));
+        let text = "Hubungi 0812-3456-7890, (021) 555-1234 atau +62 812 3456 7890.";
+        let phones: Vec<(String, bool)> = found(text)
+            .into_iter()
+            .map(|p| (p.normalized, p.valid))
+            .collect();
+        assert_eq!(
+            phones,
+            vec![
+                ("+6281234567890".to_owned(), true),
+                ("+62215551234".to_owned(), true),
+                ("+6281234567890".to_owned(), true),
+            ]
+        );
+        let nik = found("NIK: 3201 0112 0590 0001.");
+        assert_eq!(nik.len(), 1);
+        assert_eq!(nik[0].kind, IdentityKind::Nik);
+        assert_eq!(nik[0].text, "3201 0112 0590 0001");
+        assert_eq!(nik[0].normalized, "3201011205900001");
+        let details = nik[0].nik.as_ref().unwrap();
+        assert_eq!(details.province, "Jawa Barat");
+        assert_eq!(details.regency_code, "3201");
+        assert!(!details.city);
+        assert_eq!(details.birth_date, "1990-05-12");
+        assert!(!details.female);
+        let nik = found("3273015205900001");
+        let details = nik[0].nik.as_ref().unwrap();
+        assert!(details.city && details.female);
+        assert!(!found("3201013205900001")[0].valid);
+        let npwp = found("NPWP 01.234.567.8-901.000 dan NPWP 0012345678901000");
+        assert_eq!(npwp.len(), 2);
+        assert_eq!(npwp[0].kind, IdentityKind::Npwp);
+        assert_eq!(npwp[0].normalized, "01.234.567.8-901.000");
+        assert!(npwp[0].valid);
+        assert_eq!(npwp[1].kind, IdentityKind::Npwp);
+        assert!(npwp[1].valid);
+        assert_eq!(found("Rp 1.500.000.000.000.000"), vec![]);
+    }
+}
@@ -0,0 +1,148 @@
+use graph::Graph;
+use serde::Serialize;
+use tokenizer::Tokenizer;
+
+mod gazetteer;
+mod identity;
+mod rules;
+mod token;
+
+pub use gazetteer::Gazetteer;
+pub use identity::{Identity, IdentityKind, NikDetails};
+pub use rules::Rules;
+
+pub struct Ner {
+    tokenizer: Tokenizer,
+    // earlier recognizers win overlapping spans
+    recognizers: Vec<Box<dyn Recognizer + Send>>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Label {
+    Person,
+    Org,
+    Loc,
+    Date,
+    Money,
+    /// phone numbers, emails and mentions
+    Contact,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct Entity {
+    pub label: Label,
+    /// byte offset into `Graph.text`
+    pub offset: usize,
+    pub length: usize,
+    pub text: String,
+}
+
+/// Finds entities in a graph, e.g. by rules, word lists or a trained model.
+pub trait Recognizer {
+    fn recognize(&self, graph: &Graph) -> Vec<Entity>;
+}
+
+impl Default for Ner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Ner {
+    // Initialization function
+    pub fn new() -> Self {
+        Ner {
+            tokenizer: Tokenizer::new(),
+            recognizers: vec![Box::new(Rules::new()), Box::new(Gazetteer::new())],
+        }
+    }
+
+    /// Adds a recognizer which loses overlaps to the ones added before it.
+    pub fn push_recognizer(&mut self, recognizer: Box<dyn Recognizer + Send>) {
+        self.recognizers.push(recognizer);
+    }
+
+    pub fn recognize(&self, text: &str) -> Vec<Entity> {
+        let graph = self.tokenizer.parse(text.to_owned());
+        self.recognize_graph(&graph)
+    }
+
+    pub fn recognize_graph(&self, graph: &Graph) -> Vec<Entity> {
+        let mut entities: Vec<Entity> = vec![];
+        for recognizer in &self.recognizers {
+            for entity in recognizer.recognize(graph) {
+                let end = entity.offset + entity.length;
+                let overlaps = entities
+                    .iter()
+                    .any(|e| entity.offset < e.offset + e.length && e.offset < end);
+                if !overlaps {
+                    entities.push(entity);
+                }
+            }
+        }
+        entities.sort_by_key(|e| e.offset);
+        entities
+    }
+
+    /// Phone, NIK and NPWP numbers, normalized and validated.
+    pub fn identities(&self, text: &str) -> Vec<Identity> {
+        let graph = self.tokenizer.parse(text.to_owned());
+        identity::extract(&graph)
+    }
+
+    pub fn identities_graph(&self, graph: &Graph) -> Vec<Identity> {
+        identity::extract(graph)
+    }
+}
+
+pub(crate) fn entity(graph: &Graph, label: Label, offset: usize, end: usize) -> Entity {
+    Entity {
+        label,
+        offset,
+        length: end - offset,
+        text: graph.text[offset..end].to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let ner = Ner::new();
+        let found = |text: &str| -> Vec<(Label, String)> {
+            ner.recognize(text)
+                .into_iter()
+                .map(|e| (e.label, e.text))
+                .collect()
+        };
+        let text = "Bapak Budi Santoso bekerja di PT. Maju Jaya Tbk sejak 17 Agustus 2024 \
+            di Jakarta Selatan. Gajinya Rp 5.000.000, hubungi budi@maju.co.id.";
+        assert_eq!(
+            found(text),
+            vec![
+                (Label::Person, "Budi Santoso".to_owned()),
+                (Label::Org, "PT. Maju Jaya Tbk".to_owned()),
+                (Label::Date, "17 Agustus 2024".to_owned()),
+                (Label::Loc, "Jakarta Selatan".to_owned()),
+                (Label::Money, "Rp 5.000.000".to_owned()),
+                (Label::Contact, "budi@maju.co.id".to_owned()),
+            ]
+        );
+        let text = "Rapat dengan Dr. Ir. H. Ahmad Hidayat dari Telkomsel pada Senin, 2 Mei 2022, \
+            telepon 0812-3456-7890 atau +62 812 3456 7890.";
+        assert_eq!(
+            found(text),
+            vec![
+                (Label::Person, "Ahmad Hidayat".to_owned()),
+                (Label::Org, "Telkomsel".to_owned()),
+                (Label::Date, "Senin, 2 Mei 2022".to_owned()),
+                (Label::Contact, "0812-3456-7890".to_owned()),
+                (Label::Contact, "+62 812 3456 7890".to_owned()),
+            ]
+        );
+        assert_eq!(ner.recognize("Indah sekali, saya makan di jalan."), vec![]);
+    }
+}
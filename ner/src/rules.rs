@@ -0,0 +1,228 @@
+// Entities told apart by the words around them: titles before names,
+// PT or Universitas before organisations, month names in dates and so on.
+
+use graph::{Graph, TokenKind};
+use tokenizer::Tokenizer;
+
+use crate::token::{capitalized_run, Token};
+use crate::{entity, Entity, Label, Recognizer};
+
+// titles before a name, they are left out of the span
+#[rustfmt::skip]
+const PERSON_TITLES: &[&str] = &[
+    "bapak", "bpk", "bp", "pak", "ibu", "bu", "saudara", "saudari", "sdr", "sdri", "tn", "ny",
+    "nn", "dr", "drg", "drs", "dra", "prof", "ir", "h", "hj", "kh", "mas", "mbak", "kak", "mr",
+    "mrs", "ms",
+];
+
+#[rustfmt::skip]
+const ORG_MARKERS: &[&str] = &[
+    "pt", "cv", "ud", "bank", "universitas", "institut", "politeknik", "akademi", "yayasan",
+    "koperasi", "kementerian", "dinas", "badan", "komisi", "partai", "rs", "rsud", "puskesmas",
+    "sma", "smp", "smk", "sd", "perum",
+];
+
+// words after a name which still belong to the organisation
+const ORG_SUFFIXES: &[&str] = &["tbk", "persero"];
+
+#[rustfmt::skip]
+const LOC_MARKERS: &[&str] = &[
+    "jl", "jln", "jalan", "gg", "gang", "kota", "kabupaten", "kab", "provinsi", "prov",
+    "kecamatan", "kec", "kelurahan", "kel", "desa", "dusun", "pulau", "gunung", "sungai", "danau",
+    "teluk", "selat", "pantai",
+];
+
+#[rustfmt::skip]
+const MONTHS: &[&str] = &[
+    "januari", "februari", "maret", "april", "mei", "juni", "juli", "agustus", "september",
+    "oktober", "november", "desember", "jan", "feb", "mar", "apr", "jun", "jul", "agu", "agt",
+    "sep", "okt", "nov", "des",
+];
+
+const DAYS: &[&str] = &[
+    "senin", "selasa", "rabu", "kamis", "jumat", "sabtu", "minggu", "ahad",
+];
+
+const MAGNITUDES: &[&str] = &["ribu", "rb", "juta", "jt", "miliar", "milyar", "triliun"];
+
+const CURRENCY_WORDS: &[&str] = &["rupiah", "dolar", "dollar", "euro", "yen", "ringgit"];
+
+pub struct Rules {
+    tokenizer: Tokenizer,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rules {
+    // Initialization function
+    pub fn new() -> Self {
+        Rules {
+            tokenizer: Tokenizer::new(),
+        }
+    }
+}
+
+impl Recognizer for Rules {
+    fn recognize(&self, graph: &Graph) -> Vec<Entity> {
+        let sentences = match graph.sentences.is_empty() {
+            true => self.tokenizer.find_sentences(graph),
+            false => graph.sentences.clone(),
+        };
+        let tokens = Token::collect(graph, &sentences);
+        let mut entities = vec![];
+        let mut i = 0;
+        while i < tokens.len() {
+            let found = match_contact(&tokens, i)
+                .or_else(|| match_money(&tokens, i))
+                .or_else(|| match_date(&tokens, i))
+                .or_else(|| match_marked(&tokens, i));
+            match found {
+                Some((label, start, end)) => {
+                    entities.push(entity(
+                        graph,
+                        label,
+                        tokens[start].offset,
+                        tokens[end - 1].end(),
+                    ));
+                    i = end;
+                }
+                None => i += 1,
+            }
+        }
+        entities
+    }
+}
+
+// a found entity as its label and the range of tokens it spans
+type Match = Option<(Label, usize, usize)>;
+
+// "Dr." or "Jl." may be followed by a dot before the next word
+fn is_continued(tokens: &[Token], i: usize) -> bool {
+    tokens.get(i).is_some_and(|t| {
+        t.is_spaced() || (t.gap.starts_with('.') && t.gap[1..].chars().all(|c| c == ' '))
+    })
+}
+
+fn match_marked(tokens: &[Token], i: usize) -> Match {
+    let lower = tokens[i].lower();
+    if PERSON_TITLES.contains(&lower.as_str()) {
+        // Dr. Ir. H. Ahmad
+        let mut start = i + 1;
+        while is_continued(tokens, start)
+            && PERSON_TITLES.contains(&tokens[start].lower().as_str())
+            && tokens[start].is_capitalized()
+        {
+            start += 1;
+        }
+        if !is_continued(tokens, start) {
+            return None;
+        }
+        let end = capitalized_run(tokens, start);
+        return (end > start).then_some((Label::Person, start, end));
+    }
+    // "jalan" or "bank" alone are common words
+    if !tokens[i].is_capitalized() || !is_continued(tokens, i + 1) {
+        return None;
+    }
+    let end = capitalized_run(tokens, i + 1);
+    if end == i + 1 {
+        return None;
+    }
+    if ORG_MARKERS.contains(&lower.as_str()) {
+        return Some((Label::Org, i, end));
+    }
+    if LOC_MARKERS.contains(&lower.as_str()) {
+        return Some((Label::Loc, i, end));
+    }
+    // Maju Jaya Tbk
+    let last = tokens[end - 1].lower();
+    if end - i > 1 && ORG_SUFFIXES.contains(&last.as_str()) {
+        return Some((Label::Org, i, end));
+    }
+    None
+}
+
+fn is_number(token: &Token, max: usize) -> bool {
+    token.kind == TokenKind::Number && token.word.parse::<usize>().is_ok_and(|n| n <= max)
+}
+
+fn is_year(token: &Token) -> bool {
+    token.kind == TokenKind::Number && token.word.len() == 4 && token.word.parse::<u32>().is_ok()
+}
+
+fn is_month(token: &Token) -> bool {
+    token.is_word() && MONTHS.contains(&token.lower().as_str())
+}
+
+fn match_date(tokens: &[Token], i: usize) -> Match {
+    let token = &tokens[i];
+    if token.kind == TokenKind::Date {
+        return Some((Label::Date, i, i + 1));
+    }
+    // Senin, 17 Agustus 2024
+    if token.is_capitalized() && DAYS.contains(&token.lower().as_str()) {
+        let next = tokens
+            .get(i + 1)
+            .filter(|t| t.gap.trim_start_matches(',').trim().is_empty());
+        let end = match next.and_then(|_| match_date(tokens, i + 1)) {
+            Some((_, _, end)) => end,
+            None => i + 1,
+        };
+        return Some((Label::Date, i, end));
+    }
+    let spaced = |j: usize| tokens.get(j).filter(|t| t.is_spaced());
+    // 17 Agustus 2024 or 17 Agustus
+    if is_number(token, 31) && spaced(i + 1).is_some_and(is_month) {
+        let end = match spaced(i + 2).is_some_and(is_year) {
+            true => i + 3,
+            false => i + 2,
+        };
+        return Some((Label::Date, i, end));
+    }
+    // Agustus 2024
+    if token.is_capitalized() && is_month(token) && spaced(i + 1).is_some_and(is_year) {
+        return Some((Label::Date, i, i + 2));
+    }
+    None
+}
+
+fn match_money(tokens: &[Token], i: usize) -> Match {
+    let token = &tokens[i];
+    let is_word_in = |j: usize, words: &[&str]| {
+        tokens
+            .get(j)
+            .is_some_and(|t| t.is_spaced() && words.contains(&t.lower().as_str()))
+    };
+    // Rp 5.000.000 or Rp 5 juta
+    if token.kind == TokenKind::Currency {
+        let end = match is_word_in(i + 1, MAGNITUDES) {
+            true => i + 2,
+            false => i + 1,
+        };
+        return Some((Label::Money, i, end));
+    }
+    // 5 juta rupiah or 20 dolar
+    if token.kind == TokenKind::Number {
+        let mut end = i + 1;
+        if is_word_in(end, MAGNITUDES) {
+            end += 1;
+        }
+        if is_word_in(end, CURRENCY_WORDS) {
+            return Some((Label::Money, i, end + 1));
+        }
+    }
+    None
+}
+
+fn match_contact(tokens: &[Token], i: usize) -> Match {
+    match tokens[i].kind {
+        TokenKind::Email | TokenKind::Mention | TokenKind::Phone => {
+            Some((Label::Contact, i, i + 1))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,73 @@
+use graph::{Graph, Sentence, TokenKind};
+
+pub struct Token<'a> {
+    pub word: &'a str,
+    /// byte offset into `Graph.text`
+    pub offset: usize,
+    pub length: usize,
+    pub kind: TokenKind,
+    /// text between the previous token and this one
+    pub gap: &'a str,
+    pub sentence_start: bool,
+}
+
+impl<'a> Token<'a> {
+    pub fn collect(graph: &'a Graph, sentences: &[Sentence]) -> Vec<Token<'a>> {
+        let mut tokens = vec![];
+        let mut end = 0;
+        let mut sentence_start = true;
+        let mut sentences = sentences.iter().peekable();
+        for (i, lexicon) in graph.lexicons.iter().enumerate() {
+            if sentences.next_if(|s| s.lexicon == i).is_some() {
+                sentence_start = true;
+            }
+            for lexeme in lexicon.lexemes.iter() {
+                tokens.push(Token {
+                    word: graph.get_word(lexeme),
+                    offset: lexeme.offset,
+                    length: lexeme.length,
+                    kind: lexeme.metadata.kind,
+                    gap: &graph.text[end..lexeme.offset],
+                    sentence_start,
+                });
+                end = lexeme.offset + lexeme.length;
+                sentence_start = false;
+            }
+        }
+        tokens
+    }
+
+    pub fn is_word(&self) -> bool {
+        self.kind == TokenKind::Word
+    }
+
+    pub fn is_capitalized(&self) -> bool {
+        self.is_word() && self.word.chars().next().is_some_and(|c| c.is_uppercase())
+    }
+
+    pub fn lower(&self) -> String {
+        self.word.to_lowercase()
+    }
+
+    /// Only spaces separate it from the previous token.
+    pub fn is_spaced(&self) -> bool {
+        !self.gap.is_empty() && self.gap.chars().all(|c| c == ' ' || c == '\t')
+    }
+
+    pub fn end(&self) -> usize {
+        self.offset + self.length
+    }
+}
+
+/// End index (exclusive) of the capitalized words from `start` on,
+/// separated by spaces only.
+pub fn capitalized_run(tokens: &[Token], start: usize) -> usize {
+    let mut end = start;
+    while end < tokens.len()
+        && tokens[end].is_capitalized()
+        && (end == start || tokens[end].is_spaced())
+    {
+        end += 1;
+    }
+    end
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use graph::{Casing, Graph, Lexicon, TokenKind};
+use tokenizer::Tokenizer;
+
+pub struct Normalizer {
+    tokenizer: Tokenizer,
+    dictionary: HashMap<String, String>,
+}
+
+impl Default for Normalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Normalizer {
+    // Initialization function
+    pub fn new() -> Self {
+        let mut normalizer = Normalizer {
+            tokenizer: Tokenizer::new(),
+            dictionary: HashMap::new(),
+        };
+        normalizer.load_dictionary(include_str!("slang.csv"), ",");
+        normalizer
+    }
+
+    /// Load informal/formal word pairs, one pair per line.
+    /// Later entries override earlier ones.
+    pub fn load_dictionary(&mut self, corpus: &str, separator: &str) {
+        for line in corpus.lines() {
+            if let Some((informal, formal)) = line.split_once(separator) {
+                self.dictionary
+                    .insert(informal.trim().to_lowercase(), formal.trim().to_owned());
+            }
+        }
+    }
+
+    pub fn normalize_word(&self, word: &str) -> Option<&str> {
+        self.dictionary
+            .get(&word.to_lowercase())
+            .map(|s| s.as_str())
+    }
+
+    pub fn normalize(&self, text: &str) -> String {
+        let graph = self.tokenizer.parse(text.to_owned());
+        let result = self.normalize_graph(&graph);
+        self.tokenizer.render(&result)
+    }
+
+    /// Replaces informal words while keeping the rest of the text as it is.
+    pub fn normalize_graph(&self, graph: &Graph) -> Graph {
+        let mut g = Graph::new("".to_owned(), graph.using_keys);
+        for lexicon in &graph.lexicons {
+            let mut p = Lexicon::new(g.text.len());
+            p.set_prefix(g.push_str(graph.get_lexicon_prefix(lexicon)));
+            for lexeme in lexicon.lexemes.iter() {
+                let word = graph.get_word(lexeme);
+                let formal = match lexeme.metadata.kind {
+                    TokenKind::Word => self.normalize_word(word),
+                    _ => None,
+                };
+                let replaced = formal.is_some();
+                let formal = match formal {
+                    Some(formal) => Casing::detect(word).apply(formal),
+                    None => word.to_owned(),
+                };
+                // "gpp" becomes "tidak apa-apa"
+                let mut words = formal.split(' ').peekable();
+                while let Some(w) = words.next() {
+                    let mut l = g.push_word(w, graph.get_key(lexeme));
+                    // the language tag still holds for the formal word
+                    match replaced {
+                        true => {
+                            l.metadata.kind = lexeme.metadata.kind;
+                            l.metadata.language = lexeme.metadata.language.clone();
+                        }
+                        false => l.metadata = lexeme.metadata.clone(),
+                    }
+                    match words.peek() {
+                        Some(_) => l.set_suffix(g.push_str(" ")),
+                        None => l.set_suffix(g.push_str(graph.get_lexeme_suffix(lexeme))),
+                    }
+                    p.push_lexeme(l);
+                }
+            }
+            p.set_suffix(g.push_str(graph.get_lexicon_suffix(lexicon)));
+            g.lexicons.push(p);
+        }
+        g
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let mut normalizer = Normalizer::new();
+        assert_eq!(
+            normalizer.normalize("Gak udah, yg ini bgt dgn dia krn aku."),
+            "Tidak sudah, yang ini banget dengan dia karena aku."
+        );
+        assert_eq!(normalizer.normalize("gpp kok"), "tidak apa-apa kok");
+        assert_eq!(normalizer.normalize("#gak @yg"), "#gak @yg");
+        assert_eq!(normalizer.normalize("tak apa"), "tak apa");
+        let mut graph = normalizer.tokenizer.parse("gak suka weekend".to_owned());
+        for lexeme in graph.lexicons[0].lexemes.iter_mut() {
+            lexeme.metadata.language = Some("id".to_owned());
+        }
+        graph.lexicons[0].lexemes[2].metadata.language = Some("en".to_owned());
+        let languages: Vec<Option<String>> = normalizer.normalize_graph(&graph).lexicons[0]
+            .lexemes
+            .iter()
+            .map(|l| l.metadata.language.clone())
+            .collect();
+        assert_eq!(
+            languages,
+            vec![
+                Some("id".to_owned()),
+                Some("id".to_owned()),
+                Some("en".to_owned())
+            ]
+        );
+        normalizer.load_dictionary("mager;malas bergerak", ";");
+        assert_eq!(normalizer.normalize("MAGER"), "MALAS BERGERAK");
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+
+use graph::{Graph, Lexeme, TokenKind};
+use serde::Serialize;
+use tokenizer::Tokenizer;
+
+// words before a sentiment word which flip it, "tanpa masalah" is positive
+const NEGATIONS: &[&str] = &[
+    "tidak", "tak", "bukan", "belum", "jangan", "kurang", "tanpa",
+];
+
+// flipping also weakens it, "tidak jelek" is not as good as "bagus"
+const NEGATION_FACTOR: f32 = -0.75;
+
+// how far back a negation or intensifier still applies
+const MODIFIER_WINDOW: usize = 3;
+
+// words before a sentiment word which strengthen or weaken it
+const INTENSIFIERS: &[(&str, f32)] = &[
+    ("sangat", 1.5),
+    ("amat", 1.5),
+    ("sungguh", 1.4),
+    ("benar-benar", 1.4),
+    ("terlalu", 1.3),
+    ("begitu", 1.3),
+    ("paling", 1.6),
+    ("super", 1.5),
+    ("makin", 1.2),
+    ("semakin", 1.2),
+    ("cukup", 0.8),
+    ("agak", 0.7),
+    ("rada", 0.7),
+    ("sedikit", 0.6),
+];
+
+// words after it, "bagus banget"
+const POST_INTENSIFIERS: &[(&str, f32)] = &[("sekali", 1.5), ("banget", 1.5), ("amat", 1.5)];
+
+// scores are squashed into -1..1 by score / sqrt(score² + NORMALIZE_ALPHA)
+const NORMALIZE_ALPHA: f32 = 15.0;
+
+// normalized scores closer to zero than this are neutral
+const NEUTRAL_THRESHOLD: f32 = 0.05;
+
+pub struct Sentiment {
+    tokenizer: Tokenizer,
+    lexicon: HashMap<String, f32>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Polarity {
+    Positive,
+    Neutral,
+    Negative,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Analysis {
+    /// -1 (negative) to 1 (positive)
+    pub score: f32,
+    pub polarity: Polarity,
+    pub sentences: Vec<SentenceSentiment>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SentenceSentiment {
+    /// byte offset into `Graph.text`
+    pub offset: usize,
+    pub length: usize,
+    /// -1 (negative) to 1 (positive)
+    pub score: f32,
+    pub polarity: Polarity,
+    pub tokens: Vec<Contribution>,
+}
+
+/// A word which counts towards the score.
+#[derive(Serialize, Debug, Clone)]
+pub struct Contribution {
+    /// byte offset into `Graph.text`
+    pub offset: usize,
+    pub length: usize,
+    pub text: String,
+    /// lexicon score after negation and intensifiers
+    pub score: f32,
+    #[serde(skip_serializing_if = "is_false")]
+    pub negated: bool,
+    /// 1 unless an intensifier applies
+    pub intensity: f32,
+}
+
+fn is_false(b: &bool) -> bool {
+    !b
+}
+
+impl Default for Sentiment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sentiment {
+    // Initialization function
+    pub fn new() -> Self {
+        let mut sentiment = Sentiment {
+            tokenizer: Tokenizer::new(),
+            lexicon: HashMap::new(),
+        };
+        sentiment.load_lexicon(include_str!("lexicon.tsv"));
+        sentiment
+    }
+
+    /// Load tab separated words and scores (-5 to 5), one per line.
+    /// Later entries override earlier ones.
+    pub fn load_lexicon(&mut self, data: &str) {
+        for line in data.lines() {
+            let Some((word, score)) = line.split_once('\t') else {
+                continue;
+            };
+            if let Ok(score) = score.trim().parse() {
+                self.lexicon.insert(word.trim().to_lowercase(), score);
+            }
+        }
+    }
+
+    /// The negations and intensifiers, which should be kept when stop words
+    /// are removed before scoring.
+    pub fn modifiers() -> impl Iterator<Item = &'static str> {
+        NEGATIONS
+            .iter()
+            .copied()
+            .chain(INTENSIFIERS.iter().map(|(w, _)| *w))
+            .chain(POST_INTENSIFIERS.iter().map(|(w, _)| *w))
+    }
+
+    pub fn analyze(&self, text: &str) -> Analysis {
+        let graph = self.tokenizer.parse(text.to_owned());
+        self.analyze_graph(&graph)
+    }
+
+    /// Scores every sentence, works on normalized or stemmed graphs as well.
+    pub fn analyze_graph(&self, graph: &Graph) -> Analysis {
+        let sentences = match graph.sentences.is_empty() {
+            true => self.tokenizer.find_sentences(graph),
+            false => graph.sentences.clone(),
+        };
+        let mut total = 0.0;
+        let mut result = vec![];
+        for sentence in &sentences {
+            let lexicons = &graph.lexicons[sentence.lexicon..sentence.lexicon + sentence.lexicons];
+            let mut tokens = vec![];
+            // negations and intensifiers do not reach across punctuation
+            for lexicon in lexicons {
+                tokens.extend(self.score_clause(graph, &lexicon.lexemes));
+            }
+            let sum: f32 = tokens.iter().map(|t| t.score).sum();
+            total += sum;
+            let score = normalize(sum);
+            result.push(SentenceSentiment {
+                offset: sentence.offset,
+                length: sentence.length,
+                score,
+                polarity: polarity(score),
+                tokens,
+            });
+        }
+        let score = normalize(total);
+        Analysis {
+            score,
+            polarity: polarity(score),
+            sentences: result,
+        }
+    }
+
+    fn score_word(&self, graph: &Graph, lexeme: &Lexeme) -> Option<f32> {
+        if !matches!(lexeme.metadata.kind, TokenKind::Word | TokenKind::Emoji) {
+            return None;
+        }
+        let word = graph.get_word(lexeme).to_lowercase();
+        // "mengecewakan" is found by its root once the morphology is known
+        let root = lexeme.metadata.morphology.as_ref().map(|m| m.root.as_str());
+        self.lexicon
+            .get(&word)
+            .or_else(|| root.and_then(|r| self.lexicon.get(r)))
+            .copied()
+    }
+
+    fn score_clause(&self, graph: &Graph, lexemes: &[Lexeme]) -> Vec<Contribution> {
+        let words: Vec<String> = lexemes
+            .iter()
+            .map(|l| graph.get_word(l).to_lowercase())
+            .collect();
+        let scores: Vec<Option<f32>> = lexemes.iter().map(|l| self.score_word(graph, l)).collect();
+        let mut tokens = vec![];
+        for (i, lexeme) in lexemes.iter().enumerate() {
+            let Some(base) = scores[i] else {
+                continue;
+            };
+            let mut negated = false;
+            let mut intensity = 1.0;
+            // "tidak terlalu bagus", stopping at the previous sentiment word
+            for j in (i.saturating_sub(MODIFIER_WINDOW)..i).rev() {
+                if scores[j].is_some() {
+                    break;
+                }
+                if NEGATIONS.contains(&words[j].as_str()) {
+                    negated = !negated;
+                } else if let Some((_, factor)) = INTENSIFIERS.iter().find(|(w, _)| *w == words[j])
+                {
+                    intensity *= factor;
+                }
+            }
+            let post = words.get(i + 1).and_then(|next| {
+                POST_INTENSIFIERS
+                    .iter()
+                    .find(|(w, _)| w == next)
+                    .map(|(_, f)| f)
+            });
+            if let Some(factor) = post {
+                intensity *= factor;
+            }
+            let mut score = base * intensity;
+            if negated {
+                score *= NEGATION_FACTOR;
+            }
+            tokens.push(Contribution {
+                offset: lexeme.offset,
+                length: lexeme.length,
+                text: graph.get_word(lexeme).to_owned(),
+                score,
+                negated,
+                intensity,
+            });
+        }
+        tokens
+    }
+}
+
+fn normalize(score: f32) -> f32 {
+    score / (score * score + NORMALIZE_ALPHA).sqrt()
+}
+
+fn polarity(score: f32) -> Polarity {
+    if score >= NEUTRAL_THRESHOLD {
+        Polarity::Positive
+    } else if score <= -NEUTRAL_THRESHOLD {
+        Polarity::Negative
+    } else {
+        Polarity::Neutral
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let mut sentiment = Sentiment::new();
+        let analysis = sentiment
+            .analyze("Barangnya bagus banget, pengirimannya cepat. Tapi kurirnya tidak ramah!");
+        assert_eq!(analysis.polarity, Polarity::Positive);
+        assert_eq!(analysis.sentences.len(), 2);
+        let first = &analysis.sentences[0];
+        assert_eq!(first.polarity, Polarity::Positive);
+        assert_eq!(first.tokens[0].text, "bagus");
+        assert_eq!(first.tokens[0].intensity, 1.5);
+        let second = &analysis.sentences[1];
+        assert_eq!(second.polarity, Polarity::Negative);
+        assert!(second.tokens[0].negated);
+        assert_eq!(second.tokens[0].score, 3.0 * NEGATION_FACTOR);
+        let analysis =
+            sentiment.analyze("Sangat mengecewakan, barang rusak dan penjual tidak jujur.");
+        assert_eq!(analysis.polarity, Polarity::Negative);
+        assert_eq!(analysis.sentences[0].tokens.len(), 3);
+        assert_eq!(
+            sentiment.analyze("Paket sampai hari Senin.").polarity,
+            Polarity::Neutral
+        );
+        assert_eq!(
+            sentiment.analyze("Tanpa masalah 👍").polarity,
+            Polarity::Positive
+        );
+        assert!(Sentiment::modifiers().any(|w| w == "tidak"));
+        sentiment.load_lexicon("mager\t-2");
+        assert_eq!(sentiment.analyze("mager").polarity, Polarity::Negative);
+    }
+}
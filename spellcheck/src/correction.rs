@@ -0,0 +1,87 @@
+use graph::{Graph, Lexicon};
+
+use crate::symspell::{Suggestion, Verbosity};
+use crate::SpellCheck;
+
+/// Spellcheck result of a span of the original text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Correction {
+    /// byte offset into the original `Graph.text`
+    pub offset: usize,
+    /// byte length of the original text, may span more than one lexeme
+    pub length: usize,
+    pub original: String,
+    pub replacement: String,
+    pub changed: bool,
+    /// edit distance of the replacement, above the max edit distance
+    /// when the word is not in the dictionary
+    pub distance: i32,
+    /// corpus frequency of the replacement
+    pub count: i32,
+    /// other candidates, only looked up for changed or unknown words
+    pub alternatives: Vec<Suggestion>,
+}
+
+impl SpellCheck {
+    pub fn lookup_details(&self, graph: &Graph, top_n: usize) -> Vec<Correction> {
+        let mut corrections = vec![];
+        for lexicon in &graph.lexicons {
+            let lexicon_corrections = self.lookup_lexicon_details(graph, lexicon, top_n);
+            corrections.extend(self.lookup_preposition_details(graph, lexicon_corrections));
+        }
+        corrections
+    }
+
+    fn lookup_lexicon_details(
+        &self,
+        graph: &Graph,
+        lexicon: &Lexicon,
+        top_n: usize,
+    ) -> Vec<Correction> {
+        let parts = self
+            .symspell
+            .lookup_compound_parts(graph, lexicon, self.max_edit_distance);
+        parts
+            .into_iter()
+            .map(|(range, si)| {
+                let lexemes = &lexicon.lexemes[range];
+                let first = &lexemes[0];
+                let last = &lexemes[lexemes.len() - 1];
+                let offset = first.offset;
+                let length = last.offset + last.length - offset;
+                let original = &graph.text[offset..offset + length];
+                let changed = si.term != original;
+                let alternatives = if changed || si.distance > self.max_edit_distance {
+                    let word: String = lexemes.iter().map(|l| graph.get_word(l)).collect();
+                    self.lookup_alternatives(&word, &si.term, top_n)
+                } else {
+                    vec![]
+                };
+                Correction {
+                    offset,
+                    length,
+                    original: original.to_owned(),
+                    replacement: si.term,
+                    changed,
+                    distance: si.distance,
+                    count: si.count,
+                    alternatives,
+                }
+            })
+            .collect()
+    }
+
+    fn lookup_alternatives(&self, word: &str, chosen: &str, top_n: usize) -> Vec<Suggestion> {
+        let mut suggestions: Vec<Suggestion> = self
+            .symspell
+            .lookup(word, Verbosity::All, self.max_edit_distance)
+            .into_iter()
+            .filter(|s| s.term != chosen)
+            .collect();
+        // closest first, then the most frequent
+        suggestions.sort_by(|a, b| a.distance.cmp(&b.distance).then(b.count.cmp(&a.count)));
+        suggestions.truncate(top_n);
+        suggestions
+    }
+}
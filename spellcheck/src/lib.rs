@@ -1,5 +1,8 @@
+use std::sync::Arc;
+
 use deepsize::DeepSizeOf;
 use graph::Graph;
+use stemmer::{Frequency, Stemmer};
 use symspell::{Suggestion, SymSpell, Verbosity};
 use tokenizer::Tokenizer;
 
@@ -7,23 +10,40 @@ extern crate strsim;
 #[macro_use]
 extern crate derive_builder;
 
+mod correction;
+mod preposition;
 pub mod symspell;
 
+pub use correction::Correction;
+
 #[derive(DeepSizeOf)]
 pub struct SpellCheck {
     tokenizer: Tokenizer,
+    stemmer: Arc<Stemmer>,
     symspell: SymSpell,
     verbosity: Verbosity,
     max_edit_distance: i32,
 }
 
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LookupOptions {
+    /// keep the original casing, punctuation and spacing
+    pub preserve_layout: bool,
+}
+
 impl SpellCheck {
     pub fn new() -> SpellCheck {
+        SpellCheck::with_stemmer(Arc::new(Stemmer::new()))
+    }
+
+    /// Shares a stemmer which is already loaded elsewhere.
+    pub fn with_stemmer(stemmer: Arc<Stemmer>) -> SpellCheck {
         let mut symspell: SymSpell = SymSpell::default();
         let corpus = benerin_data::get_gram_words_in_csv_str();
         symspell.load_dictionary(&corpus, 0, 1, ",");
         SpellCheck {
             tokenizer: Tokenizer::new(),
+            stemmer,
             symspell,
             verbosity: Verbosity::Top,
             max_edit_distance: 2,
@@ -39,6 +59,10 @@ impl SpellCheck {
             .lookup(input, self.verbosity, self.max_edit_distance)
     }
 
+    pub fn lookup_word_with(&self, input: &str, verbosity: Verbosity) -> Vec<Suggestion> {
+        self.symspell.lookup(input, verbosity, self.max_edit_distance)
+    }
+
     pub fn lookup(&self, text: &str) -> String {
         let graph = self.tokenizer.parse(text.to_owned());
         let result = self.lookup_graph(&graph);
@@ -46,11 +70,26 @@ impl SpellCheck {
     }
 
     pub fn lookup_graph(&self, graph: &Graph) -> Graph {
-        let mut g = self
-            .symspell
-            .lookup_compound(&graph, self.max_edit_distance);
-        g.trim_end();
-        g
+        self.lookup_graph_with(graph, LookupOptions::default())
+    }
+
+    pub fn lookup_graph_with(&self, graph: &Graph, options: LookupOptions) -> Graph {
+        let mut g = self.symspell.lookup_compound_with(
+            graph,
+            self.max_edit_distance,
+            options.preserve_layout,
+        );
+        if !options.preserve_layout {
+            g.trim_end();
+        }
+        self.lookup_preposition_graph(&g)
+    }
+}
+
+// lets the stemmer prefer roots which are common in the corpus
+impl Frequency for SpellCheck {
+    fn frequency(&self, word: &str) -> usize {
+        self.symspell.word_count(word).map_or(0, |c| c as usize)
     }
 }
 
@@ -62,4 +101,73 @@ fn it_works() {
         vec![Suggestion::new("kucing", 1, 21)]
     );
     assert_eq!(spellcheck.lookup("kvcing lir"), "kucing air");
+    assert_eq!(
+        spellcheck.lookup("kvcing @budi https://kucing.id 1.500"),
+        "kucing @budi https://kucing.id 1.500"
+    );
+    assert_eq!(spellcheck.lookup("tinggal dirumah"), "tinggal di rumah");
+    assert_eq!(
+        spellcheck.lookup("nasi di makan kucing"),
+        "nasi dimakan kucing"
+    );
+
+    let graph = spellcheck.tokenizer.parse("kvcing di makan".to_owned());
+    let corrections = spellcheck.lookup_details(&graph, 3);
+    assert_eq!(corrections.len(), 2);
+    assert_eq!(corrections[0].original, "kvcing");
+    assert_eq!(corrections[0].replacement, "kucing");
+    assert!(corrections[0].changed);
+    assert_eq!(corrections[1].original, "di makan");
+    assert_eq!(corrections[1].replacement, "dimakan");
+
+    let mut graph = spellcheck
+        .tokenizer
+        .parse("kucing di makan dirumah".to_owned());
+    graph.init_hash_keys();
+    let keys: Vec<usize> = graph.lexicons[0]
+        .lexemes
+        .iter()
+        .map(|l| l.metadata.key)
+        .collect();
+    let result = spellcheck.lookup_graph(&graph);
+    let words: Vec<(&str, usize)> = result.lexicons[0]
+        .lexemes
+        .iter()
+        .map(|l| (result.get_word(l), l.metadata.key))
+        .collect();
+    assert_eq!(words[0], ("kucing", keys[0]));
+    assert_eq!(words[1], ("dimakan", keys[1]));
+    assert_eq!(words[2], ("di", keys[3]));
+    assert_eq!(words[3].0, "rumah");
+
+    let graph = spellcheck
+        .tokenizer
+        .parse("Kvcing, di makan  DIRUMAH!".to_owned());
+    let options = LookupOptions {
+        preserve_layout: true,
+    };
+    let result = spellcheck.lookup_graph_with(&graph, options);
+    assert_eq!(
+        spellcheck.tokenizer.render(&result),
+        "Kucing, dimakan  DI RUMAH!"
+    );
+
+    let mut graph = spellcheck.tokenizer.parse("kvcing lir".to_owned());
+    graph.lexicons[0].lexemes[1].metadata.language = Some("en".to_owned());
+    let result = spellcheck.lookup_graph(&graph);
+    assert_eq!(spellcheck.tokenizer.render(&result), "kucing lir");
+
+    let mut graph = spellcheck.tokenizer.parse("tinggal dirumah".to_owned());
+    for lexeme in graph.lexicons[0].lexemes.iter_mut() {
+        lexeme.metadata.language = Some("id".to_owned());
+    }
+    let result = spellcheck.lookup_preposition_graph(&graph);
+    assert_eq!(result.lexicons[0].lexemes.len(), 3);
+    assert!(result.lexicons[0]
+        .lexemes
+        .iter()
+        .all(|l| l.metadata.language.as_deref() == Some("id")));
+
+    assert!(spellcheck.frequency("rumah") > 0);
+    assert_eq!(spellcheck.frequency("qwrtz"), 0);
 }
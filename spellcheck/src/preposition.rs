@@ -0,0 +1,116 @@
+use graph::{Graph, Lexeme, Lexicon, TokenKind};
+
+use crate::{Correction, SpellCheck};
+
+impl SpellCheck {
+    /// Joins or splits "di"/"ke" depending on whether it is used as a prefix
+    /// (dimakan, kedua) or as a preposition (di rumah, ke sana).
+    pub fn lookup_preposition_graph(&self, graph: &Graph) -> Graph {
+        let mut g = Graph::new("".to_owned(), graph.using_keys);
+        for lexicon in &graph.lexicons {
+            let mut p = Lexicon::new(g.text.len());
+            p.set_prefix(g.push_str(graph.get_lexicon_prefix(lexicon)));
+            let mut lexemes = lexicon.lexemes.iter().peekable();
+            while let Some(lexeme) = lexemes.next() {
+                let word = graph.get_word(lexeme);
+                if lexeme.metadata.kind != TokenKind::Word || lexeme.metadata.is_foreign() {
+                    let mut l = g.push_word(word, graph.get_key(lexeme));
+                    inherit_metadata(&mut l, lexeme);
+                    l.set_suffix(g.push_str(graph.get_lexeme_suffix(lexeme)));
+                    p.push_lexeme(l);
+                    continue;
+                }
+                if let Some((preposition, rest)) = self.stemmer.split_preposition(word) {
+                    let mut l = g.push_word(preposition, graph.inherit_key(lexeme, preposition));
+                    inherit_metadata(&mut l, lexeme);
+                    l.set_suffix(g.push_str(" "));
+                    p.push_lexeme(l);
+                    let mut l = g.push_word(rest, None);
+                    inherit_metadata(&mut l, lexeme);
+                    l.set_suffix(g.push_str(graph.get_lexeme_suffix(lexeme)));
+                    p.push_lexeme(l);
+                    continue;
+                }
+                let joined = lexemes
+                    .peek()
+                    .filter(|next| {
+                        next.metadata.kind == TokenKind::Word && !next.metadata.is_foreign()
+                    })
+                    .and_then(|next| self.join_preposition(word, graph.get_word(next)));
+                if let Some(joined) = joined {
+                    let next = lexemes.next().unwrap();
+                    let mut l = g.push_word(&joined, graph.inherit_key(lexeme, &joined));
+                    inherit_metadata(&mut l, lexeme);
+                    l.set_suffix(g.push_str(graph.get_lexeme_suffix(next)));
+                    p.push_lexeme(l);
+                    continue;
+                }
+                let mut l = g.push_word(word, graph.get_key(lexeme));
+                inherit_metadata(&mut l, lexeme);
+                l.set_suffix(g.push_str(graph.get_lexeme_suffix(lexeme)));
+                p.push_lexeme(l);
+            }
+            p.set_suffix(g.push_str(graph.get_lexicon_suffix(lexicon)));
+            g.lexicons.push(p);
+        }
+        g
+    }
+
+    /// Same as `lookup_preposition_graph`, applied to the corrections of a lexicon.
+    pub(crate) fn lookup_preposition_details(
+        &self,
+        graph: &Graph,
+        corrections: Vec<Correction>,
+    ) -> Vec<Correction> {
+        let mut result: Vec<Correction> = vec![];
+        let mut corrections = corrections.into_iter().peekable();
+        while let Some(mut c) = corrections.next() {
+            if let Some((preposition, rest)) = self.stemmer.split_preposition(&c.replacement) {
+                c.replacement = format!("{} {}", preposition, rest);
+                c.changed = true;
+                result.push(c);
+                continue;
+            }
+            let joined = corrections
+                .peek()
+                .and_then(|next| self.join_preposition(&c.replacement, &next.replacement));
+            if let Some(joined) = joined {
+                let next = corrections.next().unwrap();
+                let length = next.offset + next.length - c.offset;
+                result.push(Correction {
+                    offset: c.offset,
+                    length,
+                    original: graph.text[c.offset..c.offset + length].to_owned(),
+                    count: self
+                        .symspell
+                        .word_count(&joined.to_lowercase())
+                        .unwrap_or(0),
+                    replacement: joined,
+                    changed: true,
+                    // joining counts as one edit, the same as a combined term
+                    distance: c.distance + next.distance + 1,
+                    alternatives: vec![],
+                });
+                continue;
+            }
+            result.push(c);
+        }
+        result
+    }
+
+    fn join_preposition(&self, preposition: &str, word: &str) -> Option<String> {
+        let joined = self.stemmer.join_preposition(preposition, word)?;
+        // only join into words which are actually written that way,
+        // "di" before a noun (di meja) is still a preposition
+        self.symspell
+            .word_count(&joined.to_lowercase())
+            .map(|_| joined)
+    }
+}
+
+// split and joined words keep what was known of the word, but not its key
+fn inherit_metadata(l: &mut Lexeme, from: &Lexeme) {
+    let key = l.metadata.key;
+    l.metadata = from.metadata.clone();
+    l.metadata.key = key;
+}
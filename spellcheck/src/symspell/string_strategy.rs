@@ -2,6 +2,7 @@ use deepsize::DeepSizeOf;
 
 
 #[derive(Clone, PartialEq, Eq, DeepSizeOf)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnicodeStringStrategy {}
 
 impl Default for UnicodeStringStrategy {
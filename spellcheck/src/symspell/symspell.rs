@@ -6,11 +6,15 @@ use std::fs::File;
 use std::hash::{Hash, Hasher};
 use std::i32;
 use std::io::{BufRead, BufReader};
+use std::ops::Range;
 use std::path::Path;
 use xxhash_rust::xxh64;
 use deepsize::DeepSizeOf;
+use graph::Casing;
 use graph::Graph;
+use graph::Lexeme;
 use graph::Lexicon;
+use graph::TokenKind;
 
 use super::composition::Composition;
 use super::edit_distance::{DistanceAlgorithm, EditDistance};
@@ -25,6 +29,14 @@ pub enum Verbosity {
     All,
 }
 
+// a corrected term with the lexemes it replaces and the text after it
+struct CompoundPart<'a> {
+    lexemes: &'a [Lexeme],
+    source: &'a str,
+    term: &'a str,
+    suffix: &'a str,
+}
+
 #[derive(Builder, PartialEq, DeepSizeOf)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SymSpell {
@@ -120,6 +132,11 @@ impl SymSpell {
         }
     }
 
+    /// Frequency count of a dictionary word, if the word is in the dictionary.
+    pub fn word_count(&self, word: &str) -> Option<i32> {
+        self.wordfreq.get(word).copied()
+    }
+
     pub fn debug_heap(&self) {
         println!(
             "  bigrams heap: {:.2?} MB",
@@ -431,35 +448,134 @@ impl SymSpell {
     /// * `max_edit_distance` - The maximum edit distance between input and suggested words.
     ///
     pub fn lookup_compound(&self, graph: &Graph, edit_distance_max: i32) -> Graph {
+        self.lookup_compound_with(graph, edit_distance_max, false)
+    }
+
+    /// Same as `lookup_compound`, optionally keeping the casing of the words
+    /// and the text around them (punctuation and spacing) from the input.
+    pub fn lookup_compound_with(
+        &self,
+        graph: &Graph,
+        edit_distance_max: i32,
+        preserve_layout: bool,
+    ) -> Graph {
+        // the dictionary is lowercase, casing is applied back to the result
+        let lowercased = preserve_layout.then(|| {
+            let mut lowercased = graph.clone();
+            lowercased.text.make_ascii_lowercase();
+            lowercased
+        });
+        let lookup_input = lowercased.as_ref().unwrap_or(graph);
         let mut g = Graph::new("".to_owned(), graph.using_keys);
         for lexicon in &graph.lexicons {
             let mut p = Lexicon::new(g.text.len());
-            self.lookup_compound_lexicon(graph, lexicon, &mut g, &mut p, edit_distance_max);
+            if preserve_layout {
+                p.set_prefix(g.push_str(graph.get_lexicon_prefix(lexicon)));
+            }
+            let parts = self.lookup_compound_parts(lookup_input, lexicon, edit_distance_max);
+            for (range, si) in parts {
+                let lexemes = &lexicon.lexemes[range];
+                let source: String = lexemes.iter().map(|l| lookup_input.get_word(l)).collect();
+                let term = if !preserve_layout {
+                    si.term
+                } else if lexemes.len() == 1 && si.term == source {
+                    graph.get_word(&lexemes[0]).to_owned()
+                } else {
+                    Casing::detect(graph.get_word(&lexemes[0])).apply(&si.term)
+                };
+                let suffix = match preserve_layout {
+                    true => graph.get_lexeme_suffix(&lexemes[lexemes.len() - 1]),
+                    false => " ",
+                };
+                let metadata = &lexemes[0].metadata;
+                if metadata.kind != TokenKind::Word || metadata.is_foreign() {
+                    let mut l = g.push_word(&term, graph.get_key(&lexemes[0]));
+                    l.metadata.kind = metadata.kind;
+                    l.metadata.language = metadata.language.clone();
+                    l.set_suffix(g.push_str(suffix));
+                    p.push_lexeme(l);
+                    continue;
+                }
+                let part = CompoundPart {
+                    lexemes,
+                    source: &source,
+                    term: &term,
+                    suffix,
+                };
+                self.push_compound_part(graph, part, &mut g, &mut p);
+            }
+            if preserve_layout {
+                p.set_suffix(g.push_str(graph.get_lexicon_suffix(lexicon)));
+            }
             g.lexicons.push(p)
         }
         g
     }
 
-    fn lookup_compound_lexicon(
+    fn push_compound_part(
         &self,
         input: &Graph,
-        text: &Lexicon,
+        part: CompoundPart,
         g: &mut Graph,
         p: &mut Lexicon,
-        edit_distance_max: i32,
     ) {
+        let CompoundPart {
+            lexemes,
+            source,
+            term,
+            suffix,
+        } = part;
+        // merged or split terms only inherit the key of the first lexeme
+        // if nothing but the spacing changed
+        let same_letters = term.replace(' ', "").eq_ignore_ascii_case(source);
+        let mut words = term.split(' ').enumerate().peekable();
+        while let Some((i, word)) = words.next() {
+            let key = match i {
+                0 if same_letters => input.inherit_key(&lexemes[0], word),
+                0 => input.get_key(&lexemes[0]),
+                _ => None,
+            };
+            let mut l = g.push_word(word, key);
+            match words.peek() {
+                Some(_) => l.set_suffix(g.push_str(" ")),
+                None => l.set_suffix(g.push_str(suffix)),
+            }
+            p.push_lexeme(l);
+        }
+    }
+
+    /// Find the best suggestion for every term of a lexicon, like `lookup_compound`
+    /// but without building a new Graph.
+    ///
+    /// Each part holds the range of lexeme indices it replaces, which spans two
+    /// lexemes when they were combined, and a suggestion which term may contain
+    /// a space when a lexeme was split. A suggestion with a distance above
+    /// `edit_distance_max` means no correction was found.
+    pub fn lookup_compound_parts(
+        &self,
+        input: &Graph,
+        text: &Lexicon,
+        edit_distance_max: i32,
+    ) -> Vec<(Range<usize>, Suggestion)> {
         //parse input string into single terms
         let term_list1 = &text.lexemes;
 
         // let mut suggestions_previous_term: Vec<Suggestion> = Vec::new();                  //suggestions for a single term
         let mut suggestions: Vec<Suggestion>;
-        let mut suggestion_parts: Vec<Suggestion> = Vec::new();
+        let mut suggestion_parts: Vec<(Range<usize>, Suggestion)> = Vec::new();
         let distance_comparer = EditDistance::new(self.distance_algorithm.clone());
 
         //translate every term to its best suggestion, otherwise it remains unchanged
         let mut last_combi = false;
 
         for (i, term) in term_list1.iter().enumerate() {
+            // entities (numbers, urls, ...) and foreign words are kept as they are
+            // and never combined
+            if term.metadata.kind != TokenKind::Word || term.metadata.is_foreign() {
+                suggestion_parts.push((i..i + 1, Suggestion::new(input.get_word(term), 0, 0)));
+                last_combi = true;
+                continue;
+            }
             suggestions = self.lookup(input.get_word(term), Verbosity::Top, edit_distance_max);
 
             //combi check, always before split
@@ -475,7 +591,7 @@ impl SymSpell {
                 );
 
                 if !suggestions_combi.is_empty() {
-                    let best1 = suggestion_parts[suggestion_parts.len() - 1].clone();
+                    let best1 = suggestion_parts[suggestion_parts.len() - 1].1.clone();
                     let best2 = if !suggestions.is_empty() {
                         suggestions[0].clone()
                     } else {
@@ -493,7 +609,7 @@ impl SymSpell {
                     {
                         suggestions_combi[0].distance += 1;
                         let last_i = suggestion_parts.len() - 1;
-                        suggestion_parts[last_i] = suggestions_combi[0].clone();
+                        suggestion_parts[last_i] = (i - 1..i + 1, suggestions_combi[0].clone());
                         last_combi = true;
                         continue;
                     }
@@ -507,7 +623,7 @@ impl SymSpell {
                     || (self.string_strategy.len(input.get_word(&term_list1[i])) == 1))
             {
                 //choose best suggestion
-                suggestion_parts.push(suggestions[0].clone());
+                suggestion_parts.push((i..i + 1, suggestions[0].clone()));
             } else {
                 let mut suggestion_split_best = if !suggestions.is_empty() {
                     //add original term
@@ -638,7 +754,7 @@ impl SymSpell {
 
                     if suggestion_split_best.term != "" {
                         //select best suggestion for split pair
-                        suggestion_parts.push(suggestion_split_best.clone());
+                        suggestion_parts.push((i..i + 1, suggestion_split_best.clone()));
                     } else {
                         let mut si = Suggestion::empty();
                         // NOTE: this effectively clamps si_count to a certain minimum value, which it can't go below
@@ -650,7 +766,7 @@ impl SymSpell {
                         si.term = input.get_word(&term_list1[i]).to_string();
                         si.count = si_count as i32;
                         si.distance = edit_distance_max + 1;
-                        suggestion_parts.push(si);
+                        suggestion_parts.push((i..i + 1, si));
                     }
                 } else {
                     let mut si = Suggestion::empty();
@@ -663,17 +779,12 @@ impl SymSpell {
                     si.term = input.get_word(&term_list1[i]).to_owned();
                     si.count = si_count as i32;
                     si.distance = edit_distance_max + 1;
-                    suggestion_parts.push(si);
+                    suggestion_parts.push((i..i + 1, si));
                 }
             }
         }
 
-        for si in suggestion_parts {
-            // TODO: key
-            let mut l = g.push_word(&si.term, None);
-            l.set_suffix(g.push_str(" "));
-            p.push_lexeme(l);
-        }
+        suggestion_parts
     }
 
     /// Divides a string into words by inserting missing spaces at the appropriate positions
@@ -0,0 +1,137 @@
+// The reverse of stemming: builds derived words (memukul, kesakitan) from
+// a root and an affix template. "N" in a template is the nasal sound of
+// meN- and peN-, which changes with the first letter of the root.
+
+use crate::Stemmer;
+
+/// Common affix templates, the prefix and the suffix split by "-".
+#[rustfmt::skip]
+pub const TEMPLATES: &[&str] = &[
+    "meN-", "meN-kan", "meN-i", "di-", "di-kan", "di-i", "ter-", "ter-kan", "ber-", "ber-an",
+    "ber-kan", "peN-", "peN-an", "per-an", "ke-an", "-an", "memper-", "memper-kan", "memper-i",
+    "diper-kan", "se-",
+];
+
+const VOWELS: &[char] = &['a', 'i', 'u', 'e', 'o'];
+
+impl Stemmer {
+    /// Builds a derived word from a root and a template,
+    /// e.g. "pukul" with "meN-kan" into "memukulkan".
+    pub fn generate(&self, root: &str, template: &str) -> Option<String> {
+        let root = root.to_lowercase();
+        if !self.engine.is_root_word(&root) {
+            return None;
+        }
+        let (prefix, suffix) = template.split_once('-')?;
+        let word = match prefix.strip_suffix('N') {
+            Some(prefix) => self.attach_nasal(prefix, &root),
+            None => attach_prefix(prefix, &root),
+        };
+        Some(word + suffix)
+    }
+
+    /// Every derived word of a root in `TEMPLATES`.
+    pub fn generate_all(&self, root: &str) -> Vec<String> {
+        let mut words: Vec<String> = vec![];
+        for template in TEMPLATES {
+            match self.generate(root, template) {
+                Some(word) if !words.contains(&word) => words.push(word),
+                _ => {}
+            }
+        }
+        words
+    }
+
+    fn attach_nasal(&self, prefix: &str, root: &str) -> String {
+        // mengebom, pengecat
+        if syllables(root) == 1 {
+            return format!("{}nge{}", prefix, root);
+        }
+        let mut chars = root.chars();
+        let first = chars.next().unwrap_or_default();
+        // consonant clusters keep their first letter (mengkritik, mensyukuri)
+        let cluster = chars.next().is_some_and(|c| !VOWELS.contains(&c));
+        let nasal = match first {
+            's' if !cluster => "ny",
+            'b' | 'f' | 'v' | 'p' => "m",
+            'c' | 'd' | 'j' | 'z' | 't' | 's' => "n",
+            'g' | 'h' | 'q' | 'k' => "ng",
+            c if VOWELS.contains(&c) => "ng",
+            _ => "",
+        };
+        let form = format!("{}{}", prefix, nasal);
+        // the tables list which root letter the nasal replaces (meny: s)
+        if !cluster {
+            for letter in self.engine.restored_letters(&form) {
+                if let Some(rest) = root.strip_prefix(letter.as_str()) {
+                    return form + rest;
+                }
+            }
+        }
+        form + root
+    }
+}
+
+fn attach_prefix(prefix: &str, root: &str) -> String {
+    if prefix == "ber" && root == "ajar" {
+        return "belajar".to_owned();
+    }
+    let mut chars = root.chars();
+    let (first, second, third) = (chars.next(), chars.next(), chars.next());
+    // berambut, perumahan
+    let drop_r = prefix.ends_with('r') && first == Some('r');
+    // bekerja, beternak
+    let drop_er = prefix == "ber"
+        && first.is_some_and(|c| !VOWELS.contains(&c))
+        && second == Some('e')
+        && third == Some('r');
+    match drop_r || drop_er {
+        true => format!("{}{}", &prefix[..prefix.len() - 1], root),
+        false => format!("{}{}", prefix, root),
+    }
+}
+
+fn syllables(word: &str) -> usize {
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = VOWELS.contains(&c);
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let stemming = Stemmer::new();
+        let generate = |root, template| stemming.generate(root, template);
+        assert_eq!(generate("pukul", "meN-"), Some("memukul".to_owned()));
+        assert_eq!(generate("sapu", "meN-"), Some("menyapu".to_owned()));
+        assert_eq!(generate("tangkap", "peN-"), Some("penangkap".to_owned()));
+        assert_eq!(generate("kupas", "meN-"), Some("mengupas".to_owned()));
+        assert_eq!(generate("ambil", "meN-"), Some("mengambil".to_owned()));
+        assert_eq!(generate("lipat", "meN-"), Some("melipat".to_owned()));
+        assert_eq!(generate("bangun", "peN-an"), Some("pembangunan".to_owned()));
+        assert_eq!(generate("bom", "meN-"), Some("mengebom".to_owned()));
+        assert_eq!(generate("kritik", "meN-"), Some("mengkritik".to_owned()));
+        assert_eq!(generate("syukur", "meN-i"), Some("mensyukuri".to_owned()));
+        assert_eq!(generate("sakit", "ke-an"), Some("kesakitan".to_owned()));
+        assert_eq!(generate("ajar", "ber-"), Some("belajar".to_owned()));
+        assert_eq!(generate("kerja", "ber-"), Some("bekerja".to_owned()));
+        assert_eq!(generate("rumah", "per-an"), Some("perumahan".to_owned()));
+        assert_eq!(generate("baru", "memper-i"), Some("memperbarui".to_owned()));
+        assert_eq!(generate("buang", "di-"), Some("dibuang".to_owned()));
+        assert_eq!(generate("qwrtz", "meN-"), None);
+        assert_eq!(generate("pukul", "meN"), None);
+        assert!(stemming
+            .generate_all("pukul")
+            .contains(&"dipukuli".to_owned()));
+    }
+}
@@ -1,36 +1,141 @@
-use graph::{Graph, Lexeme, Lexicon};
+use deepsize::DeepSizeOf;
+use graph::{Casing, Graph, Lexeme, Lexicon, Morphology, Reduplication, TokenKind};
 use postemi::Postemi;
-// use sastrawi::Sastrawi;
-use std::collections::HashSet;
+use sastrawi::LazySastrawi;
+use std::collections::{HashMap, HashSet};
 use tokenizer::Tokenizer;
 
+mod generator;
 mod postemi;
-// mod sastrawi;
+mod preposition;
+mod reduplication;
+mod sastrawi;
+mod scoring;
+mod stopwords;
 
+pub use generator::TEMPLATES;
+pub use scoring::{Frequency, RankedStem};
+pub use stopwords::{StopWordOptions, DEFAULT_STOP_LIST};
+
+#[derive(DeepSizeOf)]
 pub struct Stemmer {
     tokenizer: Tokenizer,
     stop_words: HashSet<String>,
+    // named lists picked with `StopWordOptions.list`
+    stop_lists: HashMap<String, HashSet<String>>,
     engine: Postemi,
+    sastrawi: LazySastrawi,
+    // stems with this one unless `StemOptions.engine` picks another
+    default_engine: Engine,
     pub use_stop_words: bool,
 }
 
+/// Turns a lowercase word into its root.
+pub trait StemEngine {
+    fn stem_word(&self, word: &str) -> Option<&str>;
+
+    fn stem_word_with(&self, word: &str, _frequency: Option<&dyn Frequency>) -> Option<&str> {
+        self.stem_word(word)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, DeepSizeOf)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Engine {
+    #[default]
+    Postemi,
+    /// matches stems of indexes built with Sastrawi
+    Sastrawi,
+}
+
+/// What stemming does with words it finds no root for (names, brands, loanwords).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum UnknownPolicy {
+    Drop,
+    #[default]
+    KeepOriginal,
+    KeepLowercase,
+    /// keeps the original and sets `metadata.unknown`
+    Flag,
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct StemOptions<'a> {
+    /// keep the original casing, punctuation and spacing
+    pub preserve_layout: bool,
+    /// corpus word counts to settle ambiguous roots
+    pub frequency: Option<&'a dyn Frequency>,
+    /// replaces `use_stop_words` and the default list when set
+    pub stop_words: Option<&'a StopWordOptions>,
+    pub unknown: UnknownPolicy,
+    /// the stemmer's own engine when unset
+    pub engine: Option<Engine>,
+}
+
 impl Stemmer {
     // Initialization function
     pub fn new() -> Self {
+        Stemmer::with_engine(Engine::default())
+    }
+
+    pub fn with_engine(engine: Engine) -> Self {
+        let stop_words = benerin_data::get_stop_words_in_hash_set();
         Stemmer {
             tokenizer: Tokenizer::new(),
-            stop_words: benerin_data::get_stop_words_in_hash_set(),
+            stop_lists: stopwords::builtin_stop_lists(&stop_words),
+            stop_words,
             engine: Postemi::new(),
+            sastrawi: LazySastrawi::default(),
+            default_engine: engine,
             use_stop_words: true,
         }
     }
 
+    // morphology, reduplication and prepositions always use postemi
+    fn stem_engine(&self, engine: Option<Engine>) -> &dyn StemEngine {
+        match engine.unwrap_or(self.default_engine) {
+            Engine::Postemi => &self.engine,
+            Engine::Sastrawi => self.sastrawi.get(),
+        }
+    }
+
     pub fn stem_word(&self, word: &str) -> String {
-        self.engine.stem_word(word).unwrap_or(word).to_owned()
+        self.stem_word_op(word).unwrap_or(word).to_owned()
     }
 
     pub fn stem_word_op(&self, word: &str) -> Option<&str> {
-        self.engine.stem_word(word)
+        self.stem_engine(None).stem_word(word)
+    }
+
+    pub fn stem_word_with(&self, word: &str, frequency: Option<&dyn Frequency>) -> Option<&str> {
+        self.stem_engine(None).stem_word_with(word, frequency)
+    }
+
+    /// Root of a word along with the prefixes and suffixes stripped from it.
+    pub fn stem_analysis(&self, word: &str) -> Option<Morphology> {
+        self.engine.stem_analysis(&word.to_lowercase())
+    }
+
+    pub fn stem_analysis_with(
+        &self,
+        word: &str,
+        frequency: Option<&dyn Frequency>,
+    ) -> Option<Morphology> {
+        self.engine
+            .stem_analysis_with(&word.to_lowercase(), frequency)
+    }
+
+    /// Every root the word may come from with its score, the most likely first.
+    pub fn rank_stems(&self, word: &str, frequency: Option<&dyn Frequency>) -> Vec<RankedStem> {
+        self.engine.rank_stems(&word.to_lowercase(), frequency)
+    }
+
+    /// Root and kind of a reduplicated word, e.g. "anak" for "anak-anaknya".
+    pub fn analyze_reduplication(&self, word: &str) -> Option<(&str, Reduplication)> {
+        self.engine.analyze_reduplication(&word.to_lowercase())
     }
 
     pub fn stem(&self, text: &str) -> String {
@@ -40,31 +145,143 @@ impl Stemmer {
     }
 
     pub fn stem_graph(&self, graph: &Graph) -> Graph {
+        self.stem_graph_with(graph, StemOptions::default())
+    }
+
+    pub fn stem_graph_with(&self, graph: &Graph, options: StemOptions) -> Graph {
         let mut g = Graph::new("".to_owned(), graph.using_keys);
         for lexicon in &graph.lexicons {
             let mut p = Lexicon::new(g.text.len());
+            if options.preserve_layout {
+                p.set_prefix(g.push_str(graph.get_lexicon_prefix(lexicon)));
+            }
             for lexeme in lexicon.lexemes.iter() {
-                self.stem_word_lexeme(&graph, lexeme, &mut g, &mut p);
+                if lexeme.metadata.kind != TokenKind::Word || lexeme.metadata.is_foreign() {
+                    self.push_entity_lexeme(graph, lexeme, &mut g, &mut p, options);
+                } else if options.preserve_layout {
+                    self.stem_word_lexeme_preserved(graph, lexeme, &mut g, &mut p, options);
+                } else {
+                    self.stem_word_lexeme(graph, lexeme, &mut g, &mut p, options);
+                }
+            }
+            if options.preserve_layout {
+                p.set_suffix(g.push_str(graph.get_lexicon_suffix(lexicon)));
             }
             g.lexicons.push(p)
         }
-        g.trim_end();
+        if !options.preserve_layout {
+            g.trim_end();
+        }
         g
     }
 
-    fn stem_word_lexeme(&self, graph: &Graph, lexeme: &Lexeme, g: &mut Graph, p: &mut Lexicon) {
+    /// Fills `metadata.morphology` of every word without changing the text.
+    pub fn analyze_graph(&self, graph: &mut Graph, frequency: Option<&dyn Frequency>) {
+        let mut analyses = vec![];
+        for lexicon in &graph.lexicons {
+            for lexeme in lexicon.lexemes.iter() {
+                analyses.push(match lexeme.metadata.kind {
+                    TokenKind::Word => self.stem_analysis_with(graph.get_word(lexeme), frequency),
+                    _ => None,
+                });
+            }
+        }
+        let mut analyses = analyses.into_iter();
+        for lexicon in graph.lexicons.iter_mut() {
+            for lexeme in lexicon.lexemes.iter_mut() {
+                lexeme.metadata.morphology = analyses.next().flatten();
+            }
+        }
+    }
+
+    fn stem_word_lexeme(
+        &self,
+        graph: &Graph,
+        lexeme: &Lexeme,
+        g: &mut Graph,
+        p: &mut Lexicon,
+        options: StemOptions,
+    ) {
         let ow = graph.get_word(lexeme);
-        match self.stem_word_op(&ow.to_ascii_lowercase()) {
+        let lower = ow.to_ascii_lowercase();
+        if self.is_dropped(&lower, options) {
+            return;
+        }
+        let engine = self.stem_engine(options.engine);
+        let mut w = match engine.stem_word_with(&lower, options.frequency) {
             Some(s) => {
-                if self.use_stop_words && self.stop_words.contains(s) {
+                if self.is_dropped(s, options) {
                     return;
                 }
                 let mut w = g.push_word(s, graph.get_key(lexeme));
-                w.set_suffix(g.push_str(" "));
-                p.push_lexeme(w);
+                w.metadata.reduplication = self.analyze_reduplication(ow).map(|(_, r)| r);
+                w
+            }
+            None => {
+                let word = match options.unknown {
+                    UnknownPolicy::Drop => return,
+                    UnknownPolicy::KeepLowercase => &lower,
+                    UnknownPolicy::KeepOriginal | UnknownPolicy::Flag => ow,
+                };
+                let mut w = g.push_word(word, graph.get_key(lexeme));
+                w.metadata.unknown = options.unknown == UnknownPolicy::Flag;
+                w
             }
-            None => {}
         };
+        w.set_suffix(g.push_str(" "));
+        p.push_lexeme(w);
+    }
+
+    fn is_dropped(&self, word: &str, options: StemOptions) -> bool {
+        match options.stop_words {
+            Some(stop_words) => self.is_stop_word(word, stop_words),
+            None => self.use_stop_words && self.stop_words.contains(word),
+        }
+    }
+
+    // numbers, urls, foreign words and the like are kept as they are
+    fn push_entity_lexeme(
+        &self,
+        graph: &Graph,
+        lexeme: &Lexeme,
+        g: &mut Graph,
+        p: &mut Lexicon,
+        options: StemOptions,
+    ) {
+        let mut w = g.push_word(graph.get_word(lexeme), graph.get_key(lexeme));
+        w.metadata.kind = lexeme.metadata.kind;
+        w.metadata.language = lexeme.metadata.language.clone();
+        match options.preserve_layout {
+            true => w.set_suffix(g.push_str(graph.get_lexeme_suffix(lexeme))),
+            false => w.set_suffix(g.push_str(" ")),
+        }
+        p.push_lexeme(w);
+    }
+
+    // keeps every word, dropping stop words or unknown words would break the layout
+    fn stem_word_lexeme_preserved(
+        &self,
+        graph: &Graph,
+        lexeme: &Lexeme,
+        g: &mut Graph,
+        p: &mut Lexicon,
+        options: StemOptions,
+    ) {
+        let ow = graph.get_word(lexeme);
+        let lower = ow.to_ascii_lowercase();
+        let stem = self
+            .stem_engine(options.engine)
+            .stem_word_with(&lower, options.frequency);
+        let word = match (stem, options.unknown) {
+            (Some(s), _) => Casing::detect(ow).apply(s),
+            (None, UnknownPolicy::KeepLowercase) => lower,
+            (None, _) => ow.to_owned(),
+        };
+        let mut w = g.push_word(&word, graph.get_key(lexeme));
+        w.metadata.unknown = stem.is_none() && options.unknown == UnknownPolicy::Flag;
+        w.metadata.reduplication = self.analyze_reduplication(ow).map(|(_, r)| r);
+        w.set_suffix(g.push_str(graph.get_lexeme_suffix(lexeme)));
+        p.push_lexeme(w);
     }
 }
 
@@ -79,5 +296,125 @@ mod tests {
         assert_eq!(stemming.stem("menyapu di selokan"), "sapu selokan");
         assert_eq!(stemming.stem("pemusnahan sampah"), "musnah sampah");
         assert_eq!(stemming.stem("pemrograman"), "program");
+        assert_eq!(stemming.stem_word("café"), "café");
+
+        let tokenizer = Tokenizer::new();
+        let graph = tokenizer.parse("Menari di SEKOLAHAN, ya!".to_owned());
+        let options = StemOptions {
+            preserve_layout: true,
+            ..Default::default()
+        };
+        let result = stemming.stem_graph_with(&graph, options);
+        assert_eq!(tokenizer.render(&result), "Tari di SEKOLAH, ya!");
+        assert_eq!(
+            stemming.stem("menulis di https://benerin.web.id #bermain"),
+            "tulis https://benerin.web.id #bermain"
+        );
+
+        let graph = tokenizer.parse("anak-anaknya berlari-lari".to_owned());
+        let result = stemming.stem_graph(&graph);
+        assert_eq!(tokenizer.render(&result), "anak lari");
+        let kinds: Vec<_> = result.lexicons[0]
+            .lexemes
+            .iter()
+            .map(|l| l.metadata.reduplication)
+            .collect();
+        assert_eq!(kinds, vec![Some(Reduplication::Affixed); 2]);
+
+        let keep = StopWordOptions {
+            remove: vec!["tidak".to_owned()],
+            ..Default::default()
+        };
+        let options = StemOptions {
+            stop_words: Some(&keep),
+            ..Default::default()
+        };
+        let graph = tokenizer.parse("tidak menari di sekolahan".to_owned());
+        let result = stemming.stem_graph_with(&graph, options);
+        assert_eq!(tokenizer.render(&result), "tidak tari sekolah");
+
+        assert_eq!(stemming.stem("iPhone 15 murah"), "iPhone 15 murah");
+        let graph = tokenizer.parse("Beli iPhone di Jakarta".to_owned());
+        let options = StemOptions {
+            unknown: UnknownPolicy::Flag,
+            ..Default::default()
+        };
+        let result = stemming.stem_graph_with(&graph, options);
+        assert_eq!(tokenizer.render(&result), "beli iPhone Jakarta");
+        let unknown: Vec<_> = result.lexicons[0]
+            .lexemes
+            .iter()
+            .map(|l| l.metadata.unknown)
+            .collect();
+        assert_eq!(unknown, vec![false, true, true]);
+        let options = StemOptions {
+            unknown: UnknownPolicy::Drop,
+            ..Default::default()
+        };
+        let result = stemming.stem_graph_with(&graph, options);
+        assert_eq!(tokenizer.render(&result), "beli");
+        let sastrawi = Stemmer::with_engine(Engine::Sastrawi);
+        assert_eq!(sastrawi.stem("Beli iPhone"), "beli iPhone");
+        // the engine may be picked per call as well
+        let graph = tokenizer.parse("Beli iPhone".to_owned());
+        let options = StemOptions {
+            engine: Some(Engine::Sastrawi),
+            ..Default::default()
+        };
+        let result = stemming.stem_graph_with(&graph, options);
+        assert_eq!(tokenizer.render(&result), "beli iPhone");
+        let options = StemOptions {
+            unknown: UnknownPolicy::KeepLowercase,
+            ..options
+        };
+        let result = stemming.stem_graph_with(&graph, options);
+        assert_eq!(tokenizer.render(&result), "beli iphone");
+
+        let mut graph = tokenizer.parse("menari meeting".to_owned());
+        graph.lexicons[0].lexemes[1].metadata.language = Some("en".to_owned());
+        let result = stemming.stem_graph(&graph);
+        assert_eq!(tokenizer.render(&result), "tari meeting");
+
+        let mut graph = tokenizer.parse("Pemusnahan sampah".to_owned());
+        stemming.analyze_graph(&mut graph, None);
+        let morphology = graph.lexicons[0].lexemes[0].metadata.morphology.as_ref();
+        assert_eq!(
+            morphology.map(|m| m.segments()),
+            Some("pe-musnah-an".to_owned())
+        );
+    }
+
+    // words both engines are tested against, across the prefix rules
+    #[rustfmt::skip]
+    const SHARED_WORDS: &[(&str, &str)] = &[
+        ("hancurlah", "hancur"), ("bajumu", "baju"), ("celananya", "celana"), ("belikan", "beli"),
+        ("jualan", "jual"), ("miliknyalah", "milik"), ("dibuang", "buang"), ("kesakitan", "sakit"),
+        ("sesuap", "suap"), ("beradu", "adu"), ("berambut", "rambut"), ("belajar", "ajar"),
+        ("bekerja", "kerja"), ("terasing", "asing"), ("teraup", "raup"), ("melipat", "lipat"),
+        ("membangun", "bangun"), ("memperbarui", "baru"), ("memukul", "pukul"),
+        ("mencinta", "cinta"), ("menangkap", "tangkap"), ("menghajar", "hajar"),
+        ("mengupas", "kupas"), ("menyuarakan", "suara"), ("pewarna", "warna"),
+        ("peradilan", "adil"), ("pembangunan", "bangun"), ("penasihat", "nasihat"),
+        ("pengupas", "kupas"), ("penyanyi", "nyanyi"), ("petani", "tani"),
+        ("buku-buku", "buku"), ("nikmat-ku", "nikmat"), ("menyepak-nyepak", "sepak"),
+        // taken prefix first by Sastrawi
+        ("bersekolah", "sekolah"), ("bertahan", "tahan"), ("mencapai", "capai"),
+        ("dimulai", "mulai"), ("mencintai", "cinta"), ("bertebaran", "tebar"),
+        // and suffix first
+        ("peranan", "peran"), ("medannya", "medan"), ("perekonomian", "ekonomi"),
+        ("sakitimu", "sakit"), ("memberikan", "beri"),
+    ];
+
+    #[test]
+    fn engines_agree() {
+        for engine in [Engine::Postemi, Engine::Sastrawi] {
+            let stemming = Stemmer::with_engine(engine);
+            let mismatches: Vec<_> = SHARED_WORDS
+                .iter()
+                .map(|(word, root)| (*word, *root, stemming.stem_word(word)))
+                .filter(|(_, root, stem)| root != stem)
+                .collect();
+            assert!(mismatches.is_empty(), "{:?}: {:?}", engine, mismatches);
+        }
     }
 }
@@ -2,9 +2,15 @@
 // 100% test case pass on Sastrawi with less than 100 LoC
 // Original algorithm (c) Wildan Mubarok
 
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
+use deepsize::{Context, DeepSizeOf};
 use fancy_regex::Regex;
+use graph::{Morphology, Restoration};
+
+use crate::scoring::{self, Frequency, RankedStem};
+use crate::StemEngine;
 
 pub struct Postemi {
     prefix_matches: HashMap<String, (bool, Vec<String>)>,
@@ -13,6 +19,25 @@ pub struct Postemi {
     plural_detect: Regex,
 }
 
+impl DeepSizeOf for Postemi {
+    fn deep_size_of_children(&self, context: &mut Context) -> usize {
+        // the regex is small and opaque, so it is left out
+        self.prefix_matches.deep_size_of_children(context)
+            + self.suffix_matches.deep_size_of_children(context)
+            + self.root_words.deep_size_of_children(context)
+    }
+}
+
+impl StemEngine for Postemi {
+    fn stem_word(&self, word: &str) -> Option<&str> {
+        Postemi::stem_word(self, word)
+    }
+
+    fn stem_word_with(&self, word: &str, frequency: Option<&dyn Frequency>) -> Option<&str> {
+        Postemi::stem_word_with(self, word, frequency)
+    }
+}
+
 impl Postemi {
     // Initialization function
     pub fn new() -> Self {
@@ -24,23 +49,158 @@ impl Postemi {
         }
     }
 
+    pub fn is_root_word(&self, word: &str) -> bool {
+        self.root_words.contains(word)
+    }
+
+    pub fn get_root_word(&self, word: &str) -> Option<&str> {
+        self.root_words.get(word).map(|s| s.as_str())
+    }
+
+    /// Root letters a nasal prefix replaces, e.g. "s" for "meny" (menyapu).
+    pub fn restored_letters(&self, prefix: &str) -> &[String] {
+        match self.prefix_matches.get(prefix) {
+            Some((_, letters)) => letters,
+            None => &[],
+        }
+    }
+
+    pub fn is_prefix(&self, prefix: &str) -> bool {
+        matches!(self.prefix_matches.get(prefix), Some((true, _)))
+    }
+
     // Initialization function
     pub fn stem_word(&self, word: &str) -> Option<&str> {
-        let mut suffix_offsets = vec![0];
-        let mut prefix_offsets: Vec<(usize, Vec<String>)> = vec![(0, vec![])];
-        let mut word = word;
-        let single_w: String;
-        if let Some(_) = word.find('-') {
-            single_w = self.plural_detect.replace(&word, "$1").into_owned();
-            word = &single_w;
+        self.stem_word_with(word, None)
+    }
+
+    /// Like `stem_word`, using corpus frequencies to settle ambiguous roots.
+    pub fn stem_word_with(&self, word: &str, frequency: Option<&dyn Frequency>) -> Option<&str> {
+        if word.contains('-') {
+            if let Some((root, _)) = self.analyze_reduplication(word) {
+                return Some(root);
+            }
+        }
+        let word = self.singular(word);
+        let candidates = self.find_candidates(&word);
+        let mut highest_candidate: Option<(f32, &str)> = None;
+        for c in candidates.iter() {
+            let score = self.score(&word, c, frequency);
+            // the first one found wins a tie
+            if highest_candidate.is_none_or(|(s, _)| s < score) {
+                highest_candidate = Some((score, c.root));
+            }
+        }
+        highest_candidate.map(|(_, root)| root)
+    }
+
+    /// Like `stem_word`, but also tells which affixes were stripped.
+    pub fn stem_analysis(&self, word: &str) -> Option<Morphology> {
+        self.stem_analysis_with(word, None)
+    }
+
+    pub fn stem_analysis_with(
+        &self,
+        word: &str,
+        frequency: Option<&dyn Frequency>,
+    ) -> Option<Morphology> {
+        let mut ranked = self.rank_stems(word, frequency).into_iter();
+        let mut morphology = ranked.next()?.morphology;
+        for r in ranked {
+            let root = r.morphology.root;
+            if root != morphology.root && !morphology.rejected.contains(&root) {
+                morphology.rejected.push(root);
+            }
+        }
+        Some(morphology)
+    }
+
+    /// Every root the word may come from, the most likely first.
+    pub fn rank_stems(&self, word: &str, frequency: Option<&dyn Frequency>) -> Vec<RankedStem> {
+        if word.contains('-') {
+            if let Some((root, _)) = self.analyze_reduplication(word) {
+                let morphology = Morphology {
+                    root: root.to_owned(),
+                    prefixes: vec![],
+                    suffixes: vec![],
+                    restoration: None,
+                    rejected: vec![],
+                };
+                let score = scoring::score(root, 0, false, frequency);
+                return vec![RankedStem { morphology, score }];
+            }
+        }
+        let word = self.singular(word);
+        let mut ranked: Vec<RankedStem> = vec![];
+        for c in self.find_candidates(&word).iter() {
+            ranked.push(RankedStem {
+                morphology: self.morphology(&word, c),
+                score: self.score(&word, c, frequency),
+            });
+        }
+        // stable, so the first one found stays first on a tie
+        ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+        ranked
+    }
+
+    fn morphology(&self, word: &str, c: &Candidate) -> Morphology {
+        let prefixes = self.split_prefixes(&word[..c.prefix]);
+        let suffixes = self.split_suffixes(&word[word.len() - c.suffix..]);
+        let restoration = match (c.restored, prefixes.last()) {
+            (Some(restored), Some(prefix)) => Some(Restoration {
+                prefix: prefix.to_owned(),
+                restored: restored.to_owned(),
+            }),
+            _ => None,
+        };
+        Morphology {
+            root: c.root.to_owned(),
+            prefixes,
+            suffixes,
+            restoration,
+            rejected: vec![],
+        }
+    }
+
+    fn score(&self, word: &str, c: &Candidate, frequency: Option<&dyn Frequency>) -> f32 {
+        let affixes = self.split_prefixes(&word[..c.prefix]).len()
+            + self.split_suffixes(&word[word.len() - c.suffix..]).len();
+        scoring::score(c.root, affixes, c.restored.is_some(), frequency)
+    }
+
+    fn split_prefixes(&self, prefix: &str) -> Vec<String> {
+        split_affixes(prefix, &|p| self.is_prefix(p))
+    }
+
+    fn split_suffixes(&self, suffix: &str) -> Vec<String> {
+        split_affixes(suffix, &|s| {
+            matches!(self.suffix_matches.get(s), Some(true))
+        })
+    }
+
+    // buku-buku to buku
+    fn singular<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        match word.contains('-') {
+            true => self.plural_detect.replace(word, "$1"),
+            false => Cow::Borrowed(word),
         }
+    }
+
+    // every root reachable by stripping known prefixes and suffixes
+    fn find_candidates(&self, word: &str) -> Vec<Candidate<'_>> {
+        let mut suffix_offsets = vec![0];
+        let mut prefix_offsets: Vec<(usize, &[String])> = vec![(0, &[])];
         let mut s = 0;
         loop {
             s += 1;
             if word.len() <= s {
                 break;
             }
-            match self.suffix_matches.get(&word[word.len() - s..word.len()]) {
+            // "café" has no affix halfway into its last char
+            let Some(suffix) = word.get(word.len() - s..) else {
+                continue;
+            };
+            match self.suffix_matches.get(suffix) {
                 Some(true) => suffix_offsets.push(s),
                 Some(false) => continue,
                 None => break,
@@ -52,38 +212,77 @@ impl Postemi {
             if word.len() <= p {
                 break;
             }
-            match self.prefix_matches.get(&word[0..p]) {
-                Some((true, v)) => prefix_offsets.push((p, v.to_vec())),
+            let Some(prefix) = word.get(..p) else {
+                continue;
+            };
+            match self.prefix_matches.get(prefix) {
+                Some((true, v)) => prefix_offsets.push((p, v)),
                 Some((false, _)) => continue,
                 None => break,
             }
         }
-        let mut candidates: Vec<(usize, &str)> = vec![];
+        let mut candidates: Vec<Candidate> = vec![];
         for (p, pf) in prefix_offsets.iter() {
             for s in suffix_offsets.iter() {
                 let m = &word[*p..word.len() - *s];
                 if let Some(mm) = self.root_words.get(m) {
-                    candidates.push((mm.len(), mm));
+                    candidates.push(Candidate::new(mm, *p, *s, None));
                 }
                 for pf in pf.iter() {
                     let m = pf.to_owned() + &word[*p..word.len() - *s];
                     if let Some(mm) = self.root_words.get(&m) {
-                        candidates.push((mm.len(), mm));
+                        candidates.push(Candidate::new(mm, *p, *s, Some(pf)));
                     }
                 }
             }
         }
-        if candidates.len() > 0 {
-            let mut highest_candidate: &(usize, &str) = &candidates[0];
-            for c in candidates.iter() {
-                if highest_candidate.0 < c.0 {
-                    highest_candidate = c
+        candidates
+    }
+}
+
+struct Candidate<'a> {
+    root: &'a str,
+    // byte length of the stripped prefix and suffix
+    prefix: usize,
+    suffix: usize,
+    // letter put back in front of the root (menyapu -> sapu)
+    restored: Option<&'a str>,
+}
+
+impl<'a> Candidate<'a> {
+    fn new(root: &'a str, prefix: usize, suffix: usize, restored: Option<&'a str>) -> Self {
+        Candidate {
+            root,
+            prefix,
+            suffix,
+            restored,
+        }
+    }
+}
+
+// "memper" into ["mem", "per"], left as a whole when it can't be split
+fn split_affixes(affix: &str, is_affix: &dyn Fn(&str) -> bool) -> Vec<String> {
+    fn split(affix: &str, is_affix: &dyn Fn(&str) -> bool, parts: &mut Vec<String>) -> bool {
+        if affix.is_empty() {
+            return true;
+        }
+        for (i, c) in affix.char_indices() {
+            let end = i + c.len_utf8();
+            if is_affix(&affix[..end]) {
+                parts.push(affix[..end].to_owned());
+                if split(&affix[end..], is_affix, parts) {
+                    return true;
                 }
+                parts.pop();
             }
-            return Some(highest_candidate.1);
         }
-        None
+        false
     }
+    let mut parts = vec![];
+    if !split(affix, is_affix, &mut parts) {
+        parts = vec![affix.to_owned()];
+    }
+    parts
 }
 
 #[cfg(test)]
@@ -355,6 +554,9 @@ mod tests {
         assert_eq!(stemming.stem_word("buku-buku"), Some("buku"));
         assert_eq!(stemming.stem_word("berbalas-balasan"), Some("balas"));
         assert_eq!(stemming.stem_word("bolak-balik"), Some("bolak-balik"));
+        assert_eq!(stemming.stem_word("berlari-lari"), Some("lari"));
+        assert_eq!(stemming.stem_word("anak-anaknya"), Some("anak"));
+        assert_eq!(stemming.stem_word("kemerah-merahan"), Some("merah"));
 
         // combination of prefix + suffix
         assert_eq!(stemming.stem_word("bertebaran"), Some("tebar"));
@@ -414,5 +616,28 @@ mod tests {
         );
         assert_eq!(stemming.stem_word("nikmat-ku"), Some("nikmat"));
         assert_eq!(stemming.stem_word("allah-lah"), Some("allah"));
+
+        // morphological analysis
+        let analysis = stemming.stem_analysis("memperbarui").unwrap();
+        assert_eq!(analysis.segments(), "mem-per-baru-i");
+        let analysis = stemming.stem_analysis("menyapunya").unwrap();
+        assert_eq!(analysis.segments(), "meny-sapu-nya");
+        assert_eq!(
+            analysis.restoration,
+            Some(Restoration {
+                prefix: "meny".to_owned(),
+                restored: "s".to_owned()
+            })
+        );
+        let analysis = stemming.stem_analysis("nilai").unwrap();
+        assert_eq!(analysis.segments(), "nilai");
+        assert!(analysis.rejected.contains(&"nila".to_owned()));
+
+        // ranked candidates
+        let ranked = stemming.rank_stems("nilai", None);
+        assert_eq!(ranked[0].morphology.root, "nilai");
+        assert!(ranked[0].score > ranked[1].score);
+        // accented words, as the /stems route passes on every word
+        assert!(stemming.rank_stems("café", None).is_empty());
     }
 }
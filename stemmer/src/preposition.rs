@@ -0,0 +1,135 @@
+// "di" and "ke" are both prepositions (di rumah, ke pasar) and prefixes
+// (dimakan, kedua). Which one is meant decides whether they are written
+// apart or joined with the next word.
+
+use graph::Casing;
+
+use crate::Stemmer;
+
+pub const PREPOSITIONS: &[&str] = &["di", "ke"];
+
+// words that take "di"/"ke" as a preposition
+#[rustfmt::skip]
+const LOCATIVES: &[&str] = &[
+    "sini", "situ", "sana", "mana", "atas", "bawah", "dalam", "luar", "depan", "belakang",
+    "samping", "sebelah", "tengah", "antara", "sekitar", "dekat", "seberang", "pinggir", "ujung",
+    "sisi", "balik", "hadapan", "rumah", "sekolah", "kantor", "pasar", "kampus", "kota", "desa",
+    "kampung", "toko", "warung", "masjid", "gereja", "pura", "kamar", "dapur", "kelas", "meja",
+    "kebun", "sawah", "ladang", "laut", "pantai", "gunung", "hutan", "sungai", "jalan", "bandara",
+    "stasiun", "terminal", "pelabuhan", "kantin", "perpustakaan", "halaman", "lapangan", "taman",
+    "tempat", "negara", "negeri", "pulau", "provinsi", "kabupaten", "kecamatan", "mall", "mal",
+    "kafe", "restoran", "hotel", "bank",
+];
+
+// "ke" is a prefix for ordinal numbers (kedua, ketiga)
+#[rustfmt::skip]
+const ORDINALS: &[&str] = &[
+    "dua", "tiga", "empat", "lima", "enam", "tujuh", "delapan", "sembilan", "sepuluh", "sebelas",
+    "seratus", "seribu", "berapa",
+];
+
+fn is_locative(word: &str) -> bool {
+    let word = word.strip_suffix("nya").unwrap_or(word);
+    LOCATIVES.contains(&word)
+}
+
+impl Stemmer {
+    /// Splits a word where a preposition was written as a prefix,
+    /// e.g. "dirumah" into ("di", "rumah").
+    pub fn split_preposition<'a>(&self, word: &'a str) -> Option<(&'a str, &'a str)> {
+        // lowercasing may change byte lengths ("İ"), so match on `word` itself
+        let p = PREPOSITIONS.iter().find_map(|prep| {
+            word.get(..prep.len())
+                .filter(|p| p.eq_ignore_ascii_case(prep))
+        })?;
+        let rest = &word[p.len()..];
+        let lower = word.to_lowercase();
+        if rest.is_empty() || self.engine.is_root_word(&lower) {
+            return None;
+        }
+        let rest_lower = rest.to_lowercase();
+        // "diJakarta", but not "DIJUAL" written in capitals
+        let proper_noun = Casing::detect(rest) == Casing::Title;
+        if is_locative(&rest_lower) || proper_noun {
+            Some((p, rest))
+        } else {
+            None
+        }
+    }
+
+    /// Joins a prefix which was written apart from its word,
+    /// e.g. "di" and "makan" into "dimakan".
+    pub fn join_preposition(&self, preposition: &str, word: &str) -> Option<String> {
+        let lower = preposition.to_lowercase();
+        if !PREPOSITIONS.contains(&lower.as_str()) || !self.engine.is_prefix(&lower) {
+            return None;
+        }
+        if !word.chars().next().is_some_and(|c| c.is_lowercase()) {
+            return None;
+        }
+        if is_locative(word) || self.stop_words.contains(word) {
+            return None;
+        }
+        let joined = format!("{}{}", preposition, word);
+        if lower == "ke" {
+            return if ORDINALS.contains(&word) {
+                Some(joined)
+            } else {
+                None
+            };
+        }
+        let root = self.engine.stem_word(word)?;
+        // the passive prefix takes a bare root, optionally with -kan/-i
+        // or with the per- prefix (dipertanyakan)
+        let suffix = word
+            .strip_prefix(root)
+            .or_else(|| word.strip_prefix("per")?.strip_prefix(root))?;
+        let suffix = suffix.strip_suffix("nya").unwrap_or(suffix);
+        match suffix {
+            "" | "kan" | "i" => Some(joined),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let stemming = Stemmer::new();
+        assert_eq!(stemming.split_preposition("dirumah"), Some(("di", "rumah")));
+        assert_eq!(stemming.split_preposition("Kesana"), Some(("Ke", "sana")));
+        assert_eq!(stemming.split_preposition("dimakan"), None);
+        assert_eq!(stemming.split_preposition("keluar"), None);
+        assert_eq!(stemming.split_preposition("dia"), None);
+        assert_eq!(
+            stemming.split_preposition("diJakarta"),
+            Some(("di", "Jakarta"))
+        );
+        assert_eq!(stemming.split_preposition("DIJUAL"), None);
+        assert_eq!(stemming.split_preposition("KEDUA"), None);
+        assert_eq!(stemming.split_preposition("DIRUMAH"), Some(("DI", "RUMAH")));
+        // letters whose lowercase has another byte length
+        assert_eq!(stemming.split_preposition("Dİrumah"), None);
+        assert_eq!(stemming.split_preposition("\u{212A}esana"), None);
+
+        assert_eq!(
+            stemming.join_preposition("di", "makan"),
+            Some("dimakan".to_owned())
+        );
+        assert_eq!(
+            stemming.join_preposition("di", "berikan"),
+            Some("diberikan".to_owned())
+        );
+        assert_eq!(
+            stemming.join_preposition("ke", "dua"),
+            Some("kedua".to_owned())
+        );
+        assert_eq!(stemming.join_preposition("di", "rumah"), None);
+        assert_eq!(stemming.join_preposition("di", "makanan"), None);
+        assert_eq!(stemming.join_preposition("di", "Jakarta"), None);
+        assert_eq!(stemming.join_preposition("ke", "pasar"), None);
+    }
+}
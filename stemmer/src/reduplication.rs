@@ -0,0 +1,123 @@
+// Kata ulang: buku-buku, berlari-lari, sayur-mayur, lelaki
+
+use graph::Reduplication;
+
+use crate::postemi::Postemi;
+
+// "allah-lah" or "kuasa-mu" only hyphenate a particle
+const CLITICS: &[&str] = &["lah", "kah", "tah", "pun", "ku", "mu", "nya"];
+
+const VOWELS: &[char] = &['a', 'i', 'u', 'e', 'o'];
+
+impl Postemi {
+    /// Returns the root of a reduplicated word along with its kind.
+    /// The word is expected in lowercase.
+    pub fn analyze_reduplication(&self, word: &str) -> Option<(&str, Reduplication)> {
+        match word.split_once('-') {
+            Some((left, right)) => self.analyze_hyphenated(word, left, right),
+            None => self.analyze_partial(word),
+        }
+    }
+
+    fn analyze_hyphenated(
+        &self,
+        word: &str,
+        left: &str,
+        right: &str,
+    ) -> Option<(&str, Reduplication)> {
+        // malaikat-malaikat-nya is read as malaikat-malaikatnya
+        let right = right.replace('-', "");
+        if left.is_empty() || right.is_empty() || CLITICS.contains(&right.as_str()) {
+            return None;
+        }
+        if left == right {
+            return Some((self.stem_word(left)?, Reduplication::Full));
+        }
+        if let Some(i) = common_core(left, &right) {
+            // berlari-lari is stemmed as berlari, meniru-nirukan as menirukan
+            let core = &left[i..];
+            let derived = format!("{}{}", left, &right[core.len()..]);
+            return Some((self.stem_word(&derived)?, Reduplication::Affixed));
+        }
+        if is_rhythmic(left, &right) {
+            // lexicalized pairs like bolak-balik are roots on their own
+            let root = match self.get_root_word(word) {
+                Some(root) => root,
+                None => self.stem_word(left)?,
+            };
+            return Some((root, Reduplication::Rhythmic));
+        }
+        None
+    }
+
+    // dwipurwa: the first consonant is repeated with "e" (lelaki, tetamu)
+    fn analyze_partial(&self, word: &str) -> Option<(&str, Reduplication)> {
+        let mut chars = word.chars();
+        let (c, e, r) = (chars.next()?, chars.next()?, chars.next()?);
+        if c != r || e != 'e' || VOWELS.contains(&c) {
+            return None;
+        }
+        // sesuap or kekasih are prefixed instead
+        let (prefix, rest) = word.split_at(c.len_utf8() + 1);
+        if self.is_prefix(prefix) {
+            return None;
+        }
+        let root = self.get_root_word(rest)?;
+        Some((root, Reduplication::Partial))
+    }
+}
+
+// Byte offset into `left` where its tail starts to repeat at the head of `right`,
+// the longest repeated part wins.
+fn common_core(left: &str, right: &str) -> Option<usize> {
+    left.char_indices()
+        .map(|(i, _)| i)
+        .find(|&i| left[i..].chars().count() >= 2 && right.starts_with(&left[i..]))
+}
+
+// sayur-mayur changes the first consonant, bolak-balik changes the vowels
+fn is_rhythmic(left: &str, right: &str) -> bool {
+    let consonants = |s: &str| {
+        s.chars()
+            .filter(|c| !VOWELS.contains(c))
+            .collect::<String>()
+    };
+    let tail = |s: &str| s.chars().skip(1).collect::<String>();
+    left.chars().count() > 2 && (tail(left) == tail(right) || consonants(left) == consonants(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let stemming = Postemi::new();
+        let analyze = |w| stemming.analyze_reduplication(w);
+        assert_eq!(analyze("buku-buku"), Some(("buku", Reduplication::Full)));
+        assert_eq!(
+            analyze("berlari-lari"),
+            Some(("lari", Reduplication::Affixed))
+        );
+        assert_eq!(
+            analyze("anak-anaknya"),
+            Some(("anak", Reduplication::Affixed))
+        );
+        assert_eq!(
+            analyze("kemerah-merahan"),
+            Some(("merah", Reduplication::Affixed))
+        );
+        assert_eq!(
+            analyze("sayur-mayur").map(|(_, r)| r),
+            Some(Reduplication::Rhythmic)
+        );
+        assert_eq!(
+            analyze("bolak-balik"),
+            Some(("bolak-balik", Reduplication::Rhythmic))
+        );
+        assert_eq!(analyze("lelaki"), Some(("laki", Reduplication::Partial)));
+        assert_eq!(analyze("ḱeḱa"), None);
+        assert_eq!(analyze("kuasa-mu"), None);
+        assert_eq!(analyze("rumah"), None);
+    }
+}
@@ -1,14 +1,53 @@
-/* 
 use std::collections::HashSet;
+use std::sync::OnceLock;
 
 use benerin_data::sastrawi::*;
+use deepsize::{Context, DeepSizeOf};
 use fancy_regex::Regex;
 
+use crate::StemEngine;
+
 pub struct Sastrawi {
     prefix_precedence_matches: Vec<Regex>,
     prefix_matches: Vec<Vec<Ds>>,
     suffix_matches: Vec<Vec<Ds>>,
     root_words: HashSet<String>,
+    // nikmat-Ku, buku-bukunya
+    plural_particle: Regex,
+    plural_parts: Regex,
+}
+
+impl DeepSizeOf for Sastrawi {
+    fn deep_size_of_children(&self, context: &mut Context) -> usize {
+        // the rules are small regexes, only the dictionary counts
+        self.root_words.deep_size_of_children(context)
+    }
+}
+
+/// Sastrawi, loaded the first time it is picked.
+#[derive(Default)]
+pub struct LazySastrawi(OnceLock<Sastrawi>);
+
+impl LazySastrawi {
+    pub fn get(&self) -> &Sastrawi {
+        self.0.get_or_init(Sastrawi::new)
+    }
+}
+
+impl DeepSizeOf for LazySastrawi {
+    fn deep_size_of_children(&self, context: &mut Context) -> usize {
+        self.0
+            .get()
+            .map_or(0, |sastrawi| sastrawi.deep_size_of_children(context))
+    }
+}
+
+impl StemEngine for Sastrawi {
+    fn stem_word(&self, word: &str) -> Option<&str> {
+        // words Sastrawi can't stem are returned unchanged
+        let root = Sastrawi::stem_word(self, word);
+        self.root_words.get(&root).map(|s| s.as_str())
+    }
 }
 
 impl Sastrawi {
@@ -19,6 +58,8 @@ impl Sastrawi {
             prefix_matches: get_prefix_matches(),
             suffix_matches: get_suffix_matches(),
             root_words: benerin_data::get_root_words_in_hash_set(),
+            plural_particle: Regex::new(r"^(.*)-(ku|mu|nya|lah|kah|tah|pun)$").unwrap(),
+            plural_parts: Regex::new(r"^(.*)-(.*)$").unwrap(),
         }
     }
     pub fn stem_word(&self, word: &str) -> String {
@@ -28,7 +69,7 @@ impl Sastrawi {
             self.stem_singular_word(word)
         }
     }
-     
+
     fn remove_prefixes(&self, word: &str) -> (String, bool) {
         let mut word = word.to_owned();
         let mut matched = false;
@@ -43,7 +84,7 @@ impl Sastrawi {
     fn remove_suffixes(&self, word: &str) -> (String, bool) {
         self.remove_by_matches(word, &self.suffix_matches)
     }
-    fn remove_by_matches(&self, word: &str, matches: &Vec<Vec<Ds>>) -> (String, bool) {
+    fn remove_by_matches(&self, word: &str, matches: &[Vec<Ds>]) -> (String, bool) {
         let mut word = word.to_owned();
         for submatches in matches.iter() {
             let mut word2: Option<String> = None;
@@ -56,18 +97,18 @@ impl Sastrawi {
                 }
             }
             if let Some(word3) = word2 {
-                if word3 != "" {
+                if !word3.is_empty() {
                     word = word3
                 }
             }
         }
-        return (word, false);
+        (word, false)
     }
 
     fn if_prefer_prefix_first(&self, word: &str) -> bool {
         // Iterate through the rules
         for rule in &self.prefix_precedence_matches {
-            if let Ok(_) = rule.is_match(word) {
+            if rule.is_match(word).unwrap_or(false) {
                 return true;
             }
         }
@@ -77,24 +118,18 @@ impl Sastrawi {
     fn is_plural(&self, word: &str) -> bool {
         // -ku|-mu|-nya
         // nikmat-Ku, etc
-        if let Ok(Some(captures)) = Regex::new(r"^(.*)-(ku|mu|nya|lah|kah|tah|pun)$")
-            .unwrap()
-            .captures(word)
-        {
+        if let Ok(Some(captures)) = self.plural_particle.captures(word) {
             return captures.get(1).unwrap().as_str().contains('-');
         }
         word.contains('-')
     }
 
     fn stem_plural_word(&self, word: &str) -> String {
-        if let Ok(Some(captures)) = Regex::new(r"^(.*)-(.*)$").unwrap().captures(word) {
+        if let Ok(Some(captures)) = self.plural_parts.captures(word) {
             if let (Some(root1), Some(suffix)) = (captures.get(1), captures.get(2)) {
                 let mut root2 = suffix.as_str().to_owned();
                 if ["ku", "mu", "nya", "lah", "kah", "tah", "pun"].contains(&suffix.as_str()) {
-                    if let Ok(Some(inner_captures)) = Regex::new(r"^(.*)-(.*)$")
-                        .unwrap()
-                        .captures(root1.as_str())
-                    {
+                    if let Ok(Some(inner_captures)) = self.plural_parts.captures(root1.as_str()) {
                         root2 = format!(
                             "{}-{}",
                             inner_captures.get(2).unwrap().as_str(),
@@ -365,6 +400,11 @@ mod tests {
         assert_eq!(stemming.stem_word("mengkritik"), "kritik");
 
         // CS adjusting rule precedence
+        assert!(stemming.if_prefer_prefix_first("bersekolah"));
+        assert!(stemming.if_prefer_prefix_first("dimulai"));
+        assert!(!stemming.if_prefer_prefix_first("makanan"));
+        assert!(!stemming.if_prefer_prefix_first("kemejamu"));
+        assert_eq!(stemming.stem_word("kemejamu"), "kemeja");
         assert_eq!(stemming.stem_word("bersekolah"), "sekolah");
         assert_eq!(stemming.stem_word("bertahan"), "tahan");
         assert_eq!(stemming.stem_word("mencapai"), "capai");
@@ -481,4 +521,3 @@ mod tests {
         assert_eq!(stemming.stem_word("allah-lah"), "allah");
     }
 }
-*/
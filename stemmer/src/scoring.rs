@@ -0,0 +1,70 @@
+// Ranks candidate roots of an ambiguous word. The root length stays the main
+// signal, penalties and the frequency bonus only settle roots whose length
+// differs by about one letter (mengukur: ukur over kukur).
+
+use graph::Morphology;
+
+const AFFIX_PENALTY: f32 = 0.1;
+const RESTORATION_PENALTY: f32 = 0.3;
+const FREQUENCY_SCALE: f32 = 8.0;
+const MAX_FREQUENCY_BONUS: f32 = 1.5;
+
+/// Word counts of a corpus, used to prefer common roots over rare ones.
+pub trait Frequency {
+    fn frequency(&self, word: &str) -> usize;
+}
+
+/// A candidate root of a word along with how likely it is.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RankedStem {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub morphology: Morphology,
+    pub score: f32,
+}
+
+pub(crate) fn score(
+    root: &str,
+    affixes: usize,
+    restored: bool,
+    frequency: Option<&dyn Frequency>,
+) -> f32 {
+    let mut score = root.chars().count() as f32 - AFFIX_PENALTY * affixes as f32;
+    if restored {
+        score -= RESTORATION_PENALTY;
+    }
+    if let Some(frequency) = frequency {
+        let count = frequency.frequency(root) as f32;
+        score += (count.ln_1p() / FREQUENCY_SCALE).min(MAX_FREQUENCY_BONUS);
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counts;
+
+    impl Frequency for Counts {
+        fn frequency(&self, word: &str) -> usize {
+            match word {
+                "ukur" => 20000,
+                "kukur" => 3,
+                "dan" => 1000000,
+                _ => 0,
+            }
+        }
+    }
+
+    #[test]
+    fn it_works() {
+        // without counts the longer root wins
+        assert!(score("kukur", 1, true, None) > score("ukur", 1, false, None));
+        assert!(score("kukur", 1, true, Some(&Counts)) < score("ukur", 1, false, Some(&Counts)));
+        // fewer affixes win a tie
+        assert!(score("makan", 1, false, None) > score("pakan", 1, true, None));
+        // a much longer root is never outweighed
+        assert!(score("medan", 2, false, None) > score("dan", 2, false, Some(&Counts)));
+    }
+}
@@ -0,0 +1,166 @@
+// Stop word lists and their removal, separate from stemming so a request
+// can drop stop words from text it doesn't stem, or keep them while stemming.
+
+use graph::{Graph, Lexicon, TokenKind};
+use std::collections::{HashMap, HashSet};
+
+use crate::Stemmer;
+
+/// Name of the list loaded from benerin_data.
+pub const DEFAULT_STOP_LIST: &str = "default";
+
+// negations and modal verbs change the meaning of a clause in legal text
+const LEGAL_KEEP: &[&str] = &[
+    "tidak", "bukan", "belum", "tanpa", "jangan", "kecuali", "hanya", "wajib", "harus", "dapat",
+    "boleh",
+];
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct StopWordOptions {
+    /// when false no word counts as a stop word
+    pub enabled: bool,
+    /// registered list to start from, "default" when empty
+    pub list: Option<String>,
+    /// extra stop words on top of the list
+    pub add: Vec<String>,
+    /// words kept even when the list has them
+    pub remove: Vec<String>,
+}
+
+impl Default for StopWordOptions {
+    fn default() -> Self {
+        StopWordOptions {
+            enabled: true,
+            list: None,
+            add: vec![],
+            remove: vec![],
+        }
+    }
+}
+
+pub(crate) fn builtin_stop_lists(stop_words: &HashSet<String>) -> HashMap<String, HashSet<String>> {
+    let legal = stop_words
+        .iter()
+        .filter(|w| !LEGAL_KEEP.contains(&w.as_str()))
+        .cloned()
+        .collect();
+    HashMap::from([
+        ("legal".to_owned(), legal),
+        ("none".to_owned(), HashSet::new()),
+    ])
+}
+
+impl Stemmer {
+    /// Adds or replaces a named stop word list.
+    pub fn register_stop_list(&mut self, name: &str, words: &[&str]) {
+        let words = words.iter().map(|w| w.to_lowercase()).collect();
+        self.stop_lists.insert(name.to_owned(), words);
+    }
+
+    pub fn get_stop_list(&self, name: &str) -> Option<&HashSet<String>> {
+        match name {
+            DEFAULT_STOP_LIST => Some(&self.stop_words),
+            _ => self.stop_lists.get(name),
+        }
+    }
+
+    /// Checks a lowercase word, an unknown list name counts as an empty list.
+    pub fn is_stop_word(&self, word: &str, options: &StopWordOptions) -> bool {
+        // the added and removed words may be written in any case
+        let listed = |words: &[String]| words.iter().any(|w| w.to_lowercase() == word);
+        if !options.enabled || listed(&options.remove) {
+            return false;
+        }
+        let list = self.get_stop_list(options.list.as_deref().unwrap_or(DEFAULT_STOP_LIST));
+        list.is_some_and(|l| l.contains(word)) || listed(&options.add)
+    }
+
+    /// Drops stop words from a graph, keeping the layout of the remaining text.
+    pub fn remove_stop_words(&self, graph: &Graph, options: &StopWordOptions) -> Graph {
+        let mut g = Graph::new("".to_owned(), graph.using_keys);
+        for lexicon in &graph.lexicons {
+            let mut p = Lexicon::new(g.text.len());
+            p.set_prefix(g.push_str(graph.get_lexicon_prefix(lexicon)));
+            let kept: Vec<bool> = lexicon
+                .lexemes
+                .iter()
+                .map(|l| {
+                    l.metadata.kind != TokenKind::Word
+                        || !self.is_stop_word(&graph.get_word(l).to_lowercase(), options)
+                })
+                .collect();
+            for (i, lexeme) in lexicon.lexemes.iter().enumerate() {
+                if !kept[i] {
+                    continue;
+                }
+                let mut w = g.push_word(graph.get_word(lexeme), graph.get_key(lexeme));
+                w.metadata = lexeme.metadata.clone();
+                // "pasar dan." keeps the dot next to "pasar"
+                let suffix = match kept[i + 1..].iter().any(|k| *k) {
+                    true => graph.get_lexeme_suffix(lexeme),
+                    false => graph.get_lexeme_suffix(lexicon.lexemes.last().unwrap()),
+                };
+                w.set_suffix(g.push_str(suffix));
+                p.push_lexeme(w);
+            }
+            p.set_suffix(g.push_str(graph.get_lexicon_suffix(lexicon)));
+            g.lexicons.push(p)
+        }
+        g
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokenizer::Tokenizer;
+
+    #[test]
+    fn it_works() {
+        let mut stemming = Stemmer::new();
+        let tokenizer = Tokenizer::new();
+        let remove = |s: &Stemmer, text: &str, options: &StopWordOptions| {
+            let graph = tokenizer.parse(text.to_owned());
+            tokenizer.render(&s.remove_stop_words(&graph, options))
+        };
+        let options = StopWordOptions::default();
+        assert_eq!(
+            remove(&stemming, "Saya di rumah, tidak ke pasar dan.", &options),
+            "Saya rumah, pasar."
+        );
+        let legal = StopWordOptions {
+            list: Some("legal".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(remove(&stemming, "tidak ke pasar", &legal), "tidak pasar");
+        let custom = StopWordOptions {
+            add: vec!["pasar".to_owned()],
+            remove: vec!["ke".to_owned()],
+            ..Default::default()
+        };
+        assert_eq!(remove(&stemming, "tidak ke pasar", &custom), "ke");
+        let capitalized = StopWordOptions {
+            remove: vec!["Tidak".to_owned()],
+            ..Default::default()
+        };
+        assert_eq!(
+            remove(&stemming, "Tidak ke pasar", &capitalized),
+            "Tidak pasar"
+        );
+        let disabled = StopWordOptions {
+            enabled: false,
+            ..Default::default()
+        };
+        assert!(!stemming.is_stop_word("di", &disabled));
+
+        stemming.register_stop_list("kantor", &["Rumah"]);
+        let named = StopWordOptions {
+            list: Some("kantor".to_owned()),
+            ..Default::default()
+        };
+        assert!(stemming.is_stop_word("rumah", &named));
+        assert!(!stemming.is_stop_word("di", &named));
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use graph::PosTagging;
+
+// Closed word classes. These are small enough to be listed by hand and
+// they cover most of the tokens in a running Indonesian text.
+
+#[rustfmt::skip]
+const PRONOUNS: &[&str] = &[
+    "aku", "saya", "daku", "kamu", "engkau", "kau", "anda", "dikau", "dia", "ia", "beliau", "kami",
+    "kita", "kalian", "mereka", "gue", "gua", "lu", "lo", "elo", "sini", "situ", "sana", "apa",
+    "siapa", "mana", "manakah", "apakah", "siapakah", "sesuatu", "seseorang",
+];
+
+#[rustfmt::skip]
+const DETERMINERS: &[&str] = &[
+    "ini", "itu", "para", "sang", "si", "sri", "kaum", "setiap", "tiap", "semua", "segala",
+    "seluruh", "segenap", "beberapa", "sebuah", "seorang", "seekor", "sebiji", "sejumlah",
+    "berbagai", "pelbagai", "banyak", "sedikit", "masing-masing",
+];
+
+#[rustfmt::skip]
+const ADPOSITIONS: &[&str] = &[
+    "di", "ke", "dari", "pada", "kepada", "daripada", "untuk", "bagi", "buat", "dengan", "oleh",
+    "tentang", "mengenai", "terhadap", "sejak", "semenjak", "hingga", "sampai", "menuju", "antara",
+    "demi", "tanpa", "seperti", "bagaikan", "laksana", "per", "via", "menurut", "sekitar",
+    "selama", "sepanjang", "atas", "dalam",
+];
+
+#[rustfmt::skip]
+const CONJUNCTIONS: &[&str] = &[
+    "dan", "atau", "tetapi", "tapi", "serta", "namun", "melainkan", "sedangkan", "padahal",
+    "karena", "sebab", "jika", "kalau", "jikalau", "apabila", "bila", "ketika", "sewaktu", "saat",
+    "sehingga", "agar", "supaya", "bahwa", "meskipun", "walaupun", "biarpun", "sekalipun", "lalu",
+    "kemudian", "maka", "yang", "sebelum", "sesudah", "setelah", "bahkan", "lagipula", "andaikan",
+    "seandainya", "asalkan",
+];
+
+#[rustfmt::skip]
+const PARTICLES: &[&str] = &[
+    "lah", "kah", "tah", "pun", "tidak", "tak", "bukan", "belum", "jangan", "dong", "kok", "sih",
+    "deh", "kan", "ya", "nah", "toh", "loh", "lho", "kek", "nih", "tuh",
+];
+
+#[rustfmt::skip]
+const ADVERBS: &[&str] = &[
+    "sangat", "amat", "sekali", "banget", "terlalu", "paling", "lebih", "kurang", "agak", "cukup",
+    "sudah", "telah", "akan", "sedang", "tengah", "masih", "baru", "hanya", "cuma", "juga", "pula",
+    "sering", "selalu", "pernah", "jarang", "kadang", "segera", "tentu", "mungkin", "pasti",
+    "hampir", "saja", "lagi", "sempat", "harus", "wajib", "perlu", "boleh", "bisa", "dapat",
+    "mampu", "mau", "ingin", "hendak", "sungguh", "benar-benar", "sebenarnya", "sebaiknya", "tadi",
+    "nanti", "kini", "sekarang", "kemarin", "besok",
+];
+
+#[rustfmt::skip]
+const NUMERALS: &[&str] = &[
+    "nol", "satu", "dua", "tiga", "empat", "lima", "enam", "tujuh", "delapan", "sembilan",
+    "sepuluh", "sebelas", "belas", "puluh", "ratus", "ribu", "juta", "miliar", "triliun",
+    "seratus", "seribu", "sejuta", "setengah", "separuh", "pertama", "kedua", "ketiga", "keempat",
+    "kelima",
+];
+
+#[rustfmt::skip]
+const INTERJECTIONS: &[&str] = &[
+    "wah", "aduh", "ayo", "yuk", "halo", "hai", "wow", "astaga", "oh", "eh", "hore", "ah", "cih",
+    "amboi", "aduhai", "hmm", "alhamdulillah", "astagfirullah",
+];
+
+// Open class roots which are not nouns. Roots missing from both lists are
+// tagged as nouns, which is by far the most common class.
+
+#[rustfmt::skip]
+const VERB_ROOTS: &[&str] = &[
+    "makan", "minum", "tidur", "pergi", "datang", "pulang", "lari", "jalan", "duduk", "berdiri",
+    "lihat", "dengar", "baca", "tulis", "beli", "jual", "bayar", "kirim", "terima", "ambil",
+    "bawa", "buka", "tutup", "masuk", "keluar", "naik", "turun", "tanya", "jawab", "bantu",
+    "pakai", "cari", "temu", "tunggu", "pukul", "tarik", "dorong", "lempar", "tangkap", "potong",
+    "masak", "cuci", "sapu", "mandi", "main", "kerja", "ajar", "belajar", "pikir", "tahu", "kenal",
+    "ingat", "lupa", "suka", "cinta", "benci", "tinggal", "pindah", "tiba", "berangkat", "bangun",
+    "buat", "bikin", "ganti", "hapus", "simpan", "pilih", "tanam", "petik", "panen", "jatuh",
+    "lompat", "terbang", "renang", "nyanyi", "tari", "tangis", "tawa", "senyum", "marah", "kata",
+    "bicara", "cerita", "ucap", "sebut", "panggil", "minta", "beri", "kasih", "pinjam", "sewa",
+    "tolong", "tembak", "bunuh", "mati", "hidup", "lahir", "tumbuh", "antar", "jemput", "ikut",
+    "coba", "mulai", "selesai", "henti", "lanjut", "kembali", "putar",
+];
+
+#[rustfmt::skip]
+const ADJ_ROOTS: &[&str] = &[
+    "baik", "buruk", "bagus", "jelek", "besar", "kecil", "panjang", "pendek", "tinggi", "rendah",
+    "lebar", "sempit", "tebal", "tipis", "berat", "ringan", "cepat", "lambat", "panas", "dingin",
+    "hangat", "baru", "lama", "tua", "muda", "mahal", "murah", "kaya", "miskin", "cantik",
+    "tampan", "indah", "senang", "sedih", "susah", "mudah", "sulit", "pintar", "bodoh", "rajin",
+    "malas", "kuat", "lemah", "sehat", "sakit", "bersih", "kotor", "terang", "gelap", "merah",
+    "putih", "hitam", "hijau", "biru", "kuning", "manis", "pahit", "asin", "asam", "pedas", "enak",
+    "lezat", "benar", "salah", "penting", "jauh", "dekat", "ramai", "sepi", "penuh", "kosong",
+    "basah", "kering", "keras", "lembut", "halus", "kasar", "takut", "berani", "lapar", "haus",
+    "capek", "lelah", "ramah", "jujur", "adil", "aman",
+];
+
+pub fn closed_classes() -> HashMap<&'static str, PosTagging> {
+    let mut map = HashMap::new();
+    // later entries win, so the most specific class goes last
+    for (words, pos) in [
+        (NUMERALS, PosTagging::Num),
+        (ADVERBS, PosTagging::Adv),
+        (INTERJECTIONS, PosTagging::Intj),
+        (DETERMINERS, PosTagging::Det),
+        (CONJUNCTIONS, PosTagging::Conj),
+        (ADPOSITIONS, PosTagging::Adp),
+        (PARTICLES, PosTagging::Part),
+        (PRONOUNS, PosTagging::Pron),
+    ] {
+        for w in words.iter() {
+            map.insert(*w, pos);
+        }
+    }
+    map
+}
+
+pub fn open_class_roots() -> HashMap<&'static str, PosTagging> {
+    let mut map = HashMap::new();
+    for (words, pos) in [(VERB_ROOTS, PosTagging::Verb), (ADJ_ROOTS, PosTagging::Adj)] {
+        for w in words.iter() {
+            map.insert(*w, pos);
+        }
+    }
+    map
+}
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use graph::{Graph, PosTagging};
+use stemmer::Stemmer;
+use tokenizer::Tokenizer;
+
+mod lexicon;
+
+pub struct Tagger {
+    tokenizer: Tokenizer,
+    stemmer: Arc<Stemmer>,
+    closed_classes: HashMap<&'static str, PosTagging>,
+    open_class_roots: HashMap<&'static str, PosTagging>,
+}
+
+// words that turn the following noun-tagged root into an adjective
+#[rustfmt::skip]
+const ADJ_MODIFIERS: &[&str] = &[
+    "sangat", "amat", "paling", "lebih", "kurang", "agak", "cukup", "terlalu",
+];
+
+// words that turn the following noun-tagged root into a verb
+#[rustfmt::skip]
+const VERB_MODIFIERS: &[&str] = &[
+    "akan", "sedang", "sudah", "telah", "mau", "ingin", "hendak", "bisa", "dapat", "harus",
+    "boleh", "sempat", "pernah", "mulai", "jangan", "ayo", "mari",
+];
+
+const CLITICS: &[&str] = &["nya", "ku", "mu", "lah", "kah", "pun"];
+
+struct Tagged {
+    lower: String,
+    pos: PosTagging,
+    // only bare roots are retagged by context
+    is_root: bool,
+}
+
+impl Default for Tagger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tagger {
+    // Initialization function
+    pub fn new() -> Self {
+        Tagger::with_stemmer(Arc::new(Stemmer::new()))
+    }
+
+    /// Shares a stemmer which is already loaded elsewhere.
+    pub fn with_stemmer(stemmer: Arc<Stemmer>) -> Self {
+        Tagger {
+            tokenizer: Tokenizer::new(),
+            stemmer,
+            closed_classes: lexicon::closed_classes(),
+            open_class_roots: lexicon::open_class_roots(),
+        }
+    }
+
+    pub fn stemmer(&self) -> &Stemmer {
+        &self.stemmer
+    }
+
+    pub fn tag(&self, text: &str) -> Vec<PosTagging> {
+        let mut graph = self.tokenizer.parse(text.to_owned());
+        self.tag_graph(&mut graph);
+        graph
+            .lexicons
+            .iter()
+            .flat_map(|l| l.lexemes.iter().map(|le| le.metadata.pos))
+            .collect()
+    }
+
+    pub fn tag_word(&self, word: &str) -> PosTagging {
+        self.tag_word_op(word, true).0
+    }
+
+    pub fn tag_graph(&self, graph: &mut Graph) {
+        let mut tagged: Vec<Tagged> = vec![];
+        let mut sentence_start = true;
+        for lexicon in &graph.lexicons {
+            for lexeme in lexicon.lexemes.iter() {
+                let word = graph.get_word(lexeme);
+                let (pos, is_root) = self.tag_word_op(word, sentence_start);
+                tagged.push(Tagged {
+                    lower: word.to_lowercase(),
+                    pos,
+                    is_root,
+                });
+                sentence_start = false;
+            }
+            if graph.get_lexicon_suffix(lexicon).contains(['.', '!', '?']) {
+                sentence_start = true;
+            }
+        }
+
+        self.apply_context(&mut tagged);
+
+        let mut tags = tagged.iter();
+        for lexicon in graph.lexicons.iter_mut() {
+            for lexeme in lexicon.lexemes.iter_mut() {
+                lexeme.metadata.pos = tags.next().unwrap().pos;
+            }
+        }
+    }
+
+    fn tag_word_op(&self, word: &str, sentence_start: bool) -> (PosTagging, bool) {
+        let lower = word.to_lowercase();
+        if word.chars().any(|c| c.is_ascii_digit()) {
+            return (PosTagging::Num, false);
+        }
+        if let Some(pos) = self.closed_classes.get(lower.as_str()) {
+            return (*pos, false);
+        }
+        let capitalized = word.chars().next().is_some_and(|c| c.is_uppercase());
+        let all_upper = word.chars().all(|c| !c.is_lowercase());
+        if capitalized && !sentence_start && !all_upper {
+            return (PosTagging::Propn, false);
+        }
+        match self.stemmer.stem_word_op(&lower) {
+            Some(root) if root == lower => (self.tag_root(root), true),
+            Some(root) => (self.tag_derived(&lower, root), false),
+            None if capitalized => (PosTagging::Propn, false),
+            None => (PosTagging::Unknown, false),
+        }
+    }
+
+    fn tag_root(&self, root: &str) -> PosTagging {
+        match self.open_class_roots.get(root) {
+            Some(pos) => *pos,
+            None => PosTagging::Noun,
+        }
+    }
+
+    fn tag_derived(&self, word: &str, root: &str) -> PosTagging {
+        let root_pos = self.tag_root(root);
+        let prefixed = !word.starts_with(root);
+        // the first letter of the root may be dropped (pukul -> memukul)
+        let root_tail = match root.char_indices().nth(1) {
+            Some((i, _)) => &root[i..],
+            None => root,
+        };
+        let mut suffix = match word.rfind(root_tail) {
+            Some(i) => &word[i + root_tail.len()..],
+            None => "",
+        };
+        for clitic in CLITICS {
+            if let Some(s) = suffix.strip_suffix(clitic) {
+                suffix = s;
+                break;
+            }
+        }
+
+        if prefixed {
+            if word.starts_with("ke") && suffix == "an" {
+                return PosTagging::Noun;
+            }
+            if word.starts_with("pe") {
+                return PosTagging::Noun;
+            }
+            if word.starts_with("ter") && root_pos == PosTagging::Adj && suffix.is_empty() {
+                return PosTagging::Adj;
+            }
+            for prefix in ["me", "di", "ber", "ter", "ku", "kau"] {
+                if word.starts_with(prefix) {
+                    return PosTagging::Verb;
+                }
+            }
+            if word.starts_with("se") {
+                return PosTagging::Adv;
+            }
+        }
+        match suffix {
+            "kan" | "i" => PosTagging::Verb,
+            "an" => PosTagging::Noun,
+            _ => root_pos,
+        }
+    }
+
+    fn apply_context(&self, tagged: &mut [Tagged]) {
+        for i in 1..tagged.len() {
+            if !tagged[i].is_root || tagged[i].pos != PosTagging::Noun {
+                continue;
+            }
+            let prev = tagged[i - 1].lower.as_str();
+            let next = tagged.get(i + 1).map(|t| t.lower.as_str());
+            if ADJ_MODIFIERS.contains(&prev) || next == Some("sekali") {
+                tagged[i].pos = PosTagging::Adj;
+            } else if VERB_MODIFIERS.contains(&prev) {
+                tagged[i].pos = PosTagging::Verb;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let tagger = Tagger::new();
+        assert_eq!(
+            tagger.tag("Saya makan nasi di rumah."),
+            vec![
+                PosTagging::Pron,
+                PosTagging::Verb,
+                PosTagging::Noun,
+                PosTagging::Adp,
+                PosTagging::Noun
+            ]
+        );
+        assert_eq!(
+            tagger.tag("Kemarin Budi pergi ke Jakarta dengan 2 temannya"),
+            vec![
+                PosTagging::Adv,
+                PosTagging::Propn,
+                PosTagging::Verb,
+                PosTagging::Adp,
+                PosTagging::Propn,
+                PosTagging::Adp,
+                PosTagging::Num,
+                PosTagging::Noun
+            ]
+        );
+        assert_eq!(tagger.tag_word("dimakan"), PosTagging::Verb);
+        assert_eq!(tagger.tag_word("pembangunan"), PosTagging::Noun);
+        assert_eq!(tagger.tag_word("kebersihan"), PosTagging::Noun);
+        assert_eq!(tagger.tag_word("terbaik"), PosTagging::Adj);
+        assert_eq!(tagger.tag("hatinya sangat bahagia")[2], PosTagging::Adj);
+        // accented words go through the stemmer as well
+        assert_eq!(tagger.tag("minum kopi di café").len(), 4);
+    }
+}
@@ -0,0 +1,377 @@
+// Numbers, prices, dates, urls and the like contain punctuation which the
+// parser would otherwise split on, so they are scanned ahead as a whole.
+
+use graph::TokenKind;
+
+const CURRENCIES: &[&str] = &["rp", "idr", "usd", "us$", "$", "€", "£", "¥"];
+
+const TIME_ZONES: &[&str] = &["wib", "wita", "wit"];
+
+// words before a time, "pukul 14.30"
+const TIME_WORDS: &[&str] = &["pukul", "pkl", "jam"];
+
+const URL_PREFIXES: &[&str] = &["https://", "http://", "www."];
+
+// punctuation which ends a sentence rather than an url or an email
+const TRAILING: &[char] = &['.', ',', '!', '?', ';', ':', ')', ']', '}', '"', '\''];
+
+/// Scans an entity at the start of `text`, returning its byte length and kind.
+/// The text `before` it tells "pukul 14.30" from "3.14".
+pub fn scan(text: &str, before: &str) -> Option<(usize, TokenKind)> {
+    let found = scan_url(text)
+        .or_else(|| scan_email(text))
+        .or_else(|| scan_tag(text))
+        .or_else(|| scan_emoji(text));
+    if found.is_some() {
+        return found;
+    }
+    let (len, kind) = scan_phone(text)
+        .or_else(|| scan_ordinal(text))
+        .or_else(|| scan_currency(text))
+        .or_else(|| scan_numeric(before, text))?;
+    // "10kg" or "2x" are left as words
+    match text[len..].chars().next() {
+        Some(c) if c.is_alphanumeric() => None,
+        _ => Some((len, kind)),
+    }
+}
+
+/// Whether the char starts an emoji, emoji are never part of a word.
+pub fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F300..=0x1F5FF // symbols and pictographs
+        | 0x1F600..=0x1F64F // emoticons
+        | 0x1F680..=0x1F6FF // transport and map
+        | 0x1F900..=0x1FAFF // supplemental symbols
+        | 0x1F1E6..=0x1F1FF // flags
+        | 0x2600..=0x27BF // misc symbols and dingbats
+    )
+}
+
+fn is_flag(c: char) -> bool {
+    matches!(c as u32, 0x1F1E6..=0x1F1FF)
+}
+
+// modifiers which stay in the same emoji (skin tones, variation selectors, keycaps)
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(c as u32, 0x1F3FB..=0x1F3FF | 0xFE0E | 0xFE0F | 0x20E3)
+}
+
+fn scan_emoji(text: &str) -> Option<(usize, TokenKind)> {
+    let mut chars = text.char_indices().peekable();
+    let (_, first) = chars.next()?;
+    if !is_emoji(first) {
+        return None;
+    }
+    let mut len = first.len_utf8();
+    while let Some((i, c)) = chars.next() {
+        if is_emoji_modifier(c) {
+            len = i + c.len_utf8();
+        } else if c == '\u{200D}' && chars.peek().is_some_and(|(_, c)| is_emoji(*c)) {
+            // zero width joiner sequences (👨‍👩‍👧)
+            let (j, c) = chars.next().unwrap();
+            len = j + c.len_utf8();
+        } else if is_flag(first) && is_flag(c) && i == first.len_utf8() {
+            // flags are a pair of regional indicators
+            len = i + c.len_utf8();
+        } else {
+            break;
+        }
+    }
+    Some((len, TokenKind::Emoji))
+}
+
+fn trim_trailing(text: &str, len: usize) -> usize {
+    text[..len].trim_end_matches(TRAILING).len()
+}
+
+fn scan_url(text: &str) -> Option<(usize, TokenKind)> {
+    let prefix = URL_PREFIXES.iter().find(|p| {
+        text.get(..p.len())
+            .is_some_and(|s| s.eq_ignore_ascii_case(p))
+    })?;
+    let len = text.find(char::is_whitespace).unwrap_or(text.len());
+    let len = trim_trailing(text, len);
+    if len <= prefix.len() {
+        return None;
+    }
+    Some((len, TokenKind::Url))
+}
+
+fn is_email_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '%' | '+' | '-')
+}
+
+fn scan_email(text: &str) -> Option<(usize, TokenKind)> {
+    let at = text.find(|c| !is_email_char(c))?;
+    if at == 0 || !text[at..].starts_with('@') {
+        return None;
+    }
+    let domain = &text[at + 1..];
+    let len = domain.find(|c| !is_email_char(c)).unwrap_or(domain.len());
+    let len = trim_trailing(domain, len);
+    // the domain needs a dot with something after it
+    match domain[..len].rfind('.') {
+        Some(dot) if dot > 0 && dot + 1 < len => Some((at + 1 + len, TokenKind::Email)),
+        _ => None,
+    }
+}
+
+// @mention or #hashtag
+fn scan_tag(text: &str) -> Option<(usize, TokenKind)> {
+    let kind = match text.chars().next()? {
+        '@' => TokenKind::Mention,
+        '#' => TokenKind::Hashtag,
+        _ => return None,
+    };
+    let name = &text[1..];
+    let len = name
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(name.len());
+    let len = trim_trailing(name, len);
+    match len {
+        0 => None,
+        len => Some((1 + len, kind)),
+    }
+}
+
+fn digits_len(text: &str) -> usize {
+    text.bytes().take_while(|b| b.is_ascii_digit()).count()
+}
+
+// 1.500.000 or 25.000,50
+fn number_len(text: &str) -> usize {
+    let b = text.as_bytes();
+    let mut i = digits_len(text);
+    if i == 0 {
+        return 0;
+    }
+    while i + 1 < b.len() && (b[i] == b'.' || b[i] == b',') && b[i + 1].is_ascii_digit() {
+        i += 1 + digits_len(&text[i + 1..]);
+    }
+    i
+}
+
+fn scan_ordinal(text: &str) -> Option<(usize, TokenKind)> {
+    if !text.get(..3)?.eq_ignore_ascii_case("ke-") {
+        return None;
+    }
+    match digits_len(&text[3..]) {
+        0 => None,
+        len => Some((3 + len, TokenKind::Ordinal)),
+    }
+}
+
+fn scan_currency(text: &str) -> Option<(usize, TokenKind)> {
+    for symbol in CURRENCIES {
+        match text.get(..symbol.len()) {
+            Some(s) if s.eq_ignore_ascii_case(symbol) => {}
+            _ => continue,
+        }
+        let mut i = symbol.len();
+        // Rp. 25.000 or Rp 25.000
+        if text[i..].starts_with('.') {
+            i += 1;
+        }
+        i += text[i..].len() - text[i..].trim_start_matches(' ').len();
+        let len = number_len(&text[i..]);
+        if len == 0 {
+            continue;
+        }
+        i += len;
+        // Rp 25.000,-
+        if text[i..].starts_with(",-") {
+            i += 2;
+        }
+        return Some((i, TokenKind::Currency));
+    }
+    None
+}
+
+fn scan_numeric(before: &str, text: &str) -> Option<(usize, TokenKind)> {
+    let len = number_len(text);
+    if len == 0 {
+        return None;
+    }
+    if let Some(len) = date_len(text) {
+        return Some((len, TokenKind::Date));
+    }
+    if let Some(len) = time_len(text, len, is_time_context(before)) {
+        return Some((len, TokenKind::Time));
+    }
+    if text[len..].starts_with('%') {
+        return Some((len + 1, TokenKind::Percentage));
+    }
+    Some((len, TokenKind::Number))
+}
+
+// 0812-3456-7890, (021) 555-1234 or +62 812 3456 7890
+fn scan_phone(text: &str) -> Option<(usize, TokenKind)> {
+    // 08-12-2024 is a date, not an 08xx number
+    if date_len(text).is_some() {
+        return None;
+    }
+    let b = text.as_bytes();
+    let plus = text.starts_with('+');
+    let mut i = usize::from(plus);
+    let mut len = 0;
+    let mut digits = String::new();
+    let mut paren = None;
+    while i < b.len() {
+        match b[i] {
+            c if c.is_ascii_digit() => {
+                digits.push(c as char);
+                i += 1;
+                len = i;
+            }
+            // area code in parentheses
+            b'(' if paren.is_none() && (i == 0 || b[i - 1] == b' ') => {
+                paren = Some(false);
+                i += 1;
+            }
+            b')' if paren == Some(false) && len == i && i + 1 < b.len() => {
+                paren = Some(true);
+                i += 1;
+                len = i;
+            }
+            // "0812 3456 7890" but not "08123456789 10 kali"
+            b' ' if len > 0
+                && len == i
+                && (digits_len(&text[i + 1..]) >= 3 || b.get(i + 1) == Some(&b'(')) =>
+            {
+                i += 1
+            }
+            b'-' if len > 0 && len == i && b.get(i + 1).is_some_and(u8::is_ascii_digit) => i += 1,
+            _ => break,
+        }
+    }
+    if paren == Some(false) {
+        return None;
+    }
+    let local = match digits.strip_prefix("62") {
+        Some(local) => local,
+        // other countries, up to 15 digits
+        None if plus => {
+            let valid = (8..=15).contains(&digits.len()) && !digits.starts_with('0');
+            return valid.then_some((len, TokenKind::Phone));
+        }
+        None => digits.strip_prefix('0')?,
+    };
+    // the number after the country code or trunk prefix starts with 2 to 9
+    let valid = (8..=12).contains(&local.len()) && local.starts_with(|c| ('2'..='9').contains(&c));
+    valid.then_some((len, TokenKind::Phone))
+}
+
+// 12/08/2024, 12-08-24 or 2024-08-12
+fn date_len(text: &str) -> Option<usize> {
+    let day = digits_len(text);
+    let sep = *text.as_bytes().get(day)?;
+    if !(1..=4).contains(&day) || (sep != b'/' && sep != b'-') {
+        return None;
+    }
+    let month = digits_len(&text[day + 1..]);
+    let i = day + 1 + month;
+    if !(1..=2).contains(&month) || text.as_bytes().get(i) != Some(&sep) {
+        return None;
+    }
+    let year = digits_len(&text[i + 1..]);
+    if !(2..=4).contains(&year) {
+        return None;
+    }
+    Some(i + 1 + year)
+}
+
+// the word before is "pukul" or "jam"
+fn is_time_context(before: &str) -> bool {
+    if !before.ends_with(char::is_whitespace) {
+        return false;
+    }
+    let word = before.split_whitespace().last().unwrap_or("");
+    let word = word.trim_end_matches('.');
+    TIME_WORDS.iter().any(|w| w.eq_ignore_ascii_case(word))
+}
+
+// 14:30, or 14.30 after "pukul" or before the time zone
+fn time_len(text: &str, number_len: usize, context: bool) -> Option<usize> {
+    let hour = digits_len(text);
+    let sep = *text.as_bytes().get(hour)?;
+    if !(1..=2).contains(&hour) || (sep != b'.' && sep != b':') {
+        return None;
+    }
+    let minute = digits_len(&text[hour + 1..]);
+    let mut i = hour + 1 + minute;
+    // "1.500" is a number, "14.30.15" is not a time either
+    if minute != 2 || (sep == b'.' && number_len != i) {
+        return None;
+    }
+    let h: u32 = text[..hour].parse().ok()?;
+    let m: u32 = text[hour + 1..i].parse().ok()?;
+    if h > 23 || m > 59 {
+        return None;
+    }
+    let zone = text[i..]
+        .strip_prefix(' ')
+        .and_then(|rest| TIME_ZONES.iter().find(|z| starts_with_word(rest, z)));
+    if let Some(zone) = zone {
+        i += 1 + zone.len();
+    } else if sep == b'.' && !context {
+        // "3.14" is a decimal
+        return None;
+    }
+    Some(i)
+}
+
+fn starts_with_word(text: &str, word: &str) -> bool {
+    match (text.get(..word.len()), text.get(word.len()..)) {
+        (Some(w), Some(rest)) => {
+            w.eq_ignore_ascii_case(word) && !rest.starts_with(char::is_alphanumeric)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        assert_eq!(scan("1.500.000 rupiah", ""), Some((9, TokenKind::Number)));
+        assert_eq!(scan("Rp 25.000,50.", ""), Some((12, TokenKind::Currency)));
+        assert_eq!(scan("Rp.25.000,-", ""), Some((11, TokenKind::Currency)));
+        assert_eq!(scan("12/08/2024", ""), Some((10, TokenKind::Date)));
+        assert_eq!(scan("14.30 WIB", ""), Some((9, TokenKind::Time)));
+        assert_eq!(scan("14.30 WITA", ""), Some((10, TokenKind::Time)));
+        assert_eq!(scan("14:30", ""), Some((5, TokenKind::Time)));
+        assert_eq!(scan("14.30", "pukul "), Some((5, TokenKind::Time)));
+        assert_eq!(scan("3.14", ""), Some((4, TokenKind::Number)));
+        assert_eq!(scan("12.30", "nilai "), Some((5, TokenKind::Number)));
+        assert_eq!(scan("50%", ""), Some((3, TokenKind::Percentage)));
+        assert_eq!(scan("ke-3", ""), Some((4, TokenKind::Ordinal)));
+        assert_eq!(scan("0812-3456-7890.", ""), Some((14, TokenKind::Phone)));
+        assert_eq!(scan("+62 812 3456 7890", ""), Some((17, TokenKind::Phone)));
+        assert_eq!(scan("(021) 555-1234", ""), Some((14, TokenKind::Phone)));
+        assert_eq!(
+            scan("08123456789 10 kali", ""),
+            Some((11, TokenKind::Phone))
+        );
+        assert_eq!(scan("08-12-2024", ""), Some((10, TokenKind::Date)));
+        assert_eq!(scan("0812 orang", ""), Some((4, TokenKind::Number)));
+        assert_eq!(scan("10kg", ""), None);
+        assert_eq!(scan("rumah", ""), None);
+        assert_eq!(scan("rp", ""), None);
+        assert_eq!(
+            scan("https://benerin.web.id/a?b=c.", ""),
+            Some((28, TokenKind::Url))
+        );
+        assert_eq!(
+            scan("halo@benerin.web.id!", ""),
+            Some((19, TokenKind::Email))
+        );
+        assert_eq!(scan("@benerin_id.", ""), Some((11, TokenKind::Mention)));
+        assert_eq!(scan("#SelamatPagi,", ""), Some((12, TokenKind::Hashtag)));
+        assert_eq!(scan("👍🏽mantap", ""), Some((8, TokenKind::Emoji)));
+        assert_eq!(scan("🇮🇩🇮🇩", ""), Some((8, TokenKind::Emoji)));
+        assert_eq!(scan("halo@", ""), None);
+    }
+}
@@ -4,8 +4,12 @@ use deepsize::DeepSizeOf;
 use graph::Graph;
 use serde_json::Error;
 
+mod entity;
 mod parser;
 mod renderer;
+mod reparse;
+mod sentence;
+pub use reparse::{fits, TextEdit};
 #[derive(DeepSizeOf)]
 pub struct Tokenizer {
     punctuations: HashSet<char>,
@@ -34,6 +38,7 @@ impl Tokenizer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use graph::{PosTagging, TokenKind};
 
     #[test]
     fn it_works() {
@@ -44,6 +49,34 @@ mod tests {
         assert_eq!(sj, tokenizer.render_flat(&tokenizer.parse(s.to_owned())));
         let sj = r#"[{"lexemes":[{"offset":1,"length":4,"suffix":0}],"offset":0,"prefix":1,"length":4,"suffix":1},{"lexemes":[{"offset":7,"length":3,"suffix":1},{"offset":11,"length":5,"suffix":0}],"offset":6,"prefix":1,"length":9,"suffix":1},{"lexemes":[],"offset":17,"prefix":0,"length":0,"suffix":1},{"lexemes":[{"offset":19,"length":5,"suffix":2},{"offset":26,"length":4,"suffix":1},{"offset":31,"length":2,"suffix":0}],"offset":18,"prefix":1,"length":14,"suffix":1},{"lexemes":[],"offset":34,"prefix":1,"length":0,"suffix":0}]"#;
         assert_eq!(sj, tokenizer.to_json(&tokenizer.parse(s.to_owned())).unwrap());
+        let s = "Harganya Rp 25.000,50 pada 12/08/2024 pukul 14.30 WIB.";
+        let graph = tokenizer.parse(s.to_owned());
+        let kinds: Vec<(&str, TokenKind)> = graph.lexicons[0]
+            .lexemes
+            .iter()
+            .map(|l| (graph.get_word(l), l.metadata.kind))
+            .collect();
+        assert_eq!(kinds[1], ("Rp 25.000,50", TokenKind::Currency));
+        assert_eq!(kinds[3], ("12/08/2024", TokenKind::Date));
+        assert_eq!(kinds[5], ("14.30 WIB", TokenKind::Time));
+        assert_eq!(s, tokenizer.render(&graph));
+        // words ending or made of a multi-byte char
+        let graph = tokenizer.parse("kopi café é enak".to_owned());
+        let words: Vec<&str> = graph.lexicons[0]
+            .lexemes
+            .iter()
+            .map(|l| graph.get_word(l))
+            .collect();
+        assert_eq!(words, vec!["kopi", "café", "é", "enak"]);
+        let graph = tokenizer.parse("Dasar bodoh, pergi!".to_owned());
+        assert_eq!(
+            tokenizer.render_masked(&graph, &[(6, 5)]),
+            "Dasar b****, pergi!"
+        );
+        // metadata from clients may leave the key out
+        let sj = r#"{"text":"rumah","lexicons":[{"lexemes":[{"offset":0,"length":5,"suffix":0,"metadata":{"pos":"Noun"}}],"offset":0,"prefix":0,"length":5,"suffix":0}],"using_keys":false}"#;
+        let graph = tokenizer.from_json(sj).unwrap();
+        assert_eq!(graph.lexicons[0].lexemes[0].metadata.pos, PosTagging::Noun);
         let sj = r#"[]"#;
         assert_eq!(sj, tokenizer.to_json(&tokenizer.parse("".to_owned())).unwrap());
         let sj = r#"[{"lexemes":[],"offset":0,"prefix":1,"length":0,"suffix":0}]"#;
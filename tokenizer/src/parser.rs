@@ -1,21 +1,50 @@
 use graph::{Graph, Lexeme, Lexicon};
 
-use crate::Tokenizer;
+use crate::{entity, Tokenizer};
 
 impl Tokenizer {
     pub fn parse<'a>(&'a self, text: String) -> Graph {
-        let mut lexicons: Vec<Lexicon> = vec![];
         let mut g = Graph::new(text, false);
+        let (lexicons, _) = self.parse_from(&g.text, 0, |_| None);
+        g.lexicons = lexicons;
+        g
+    }
+
+    /// Lexicons of `text` from `start` on, which has to begin a lexicon.
+    /// Stops once `resume` returns something for the offset a new lexicon
+    /// begins at, and passes that on.
+    pub(crate) fn parse_from(
+        &self,
+        text: &str,
+        start: usize,
+        resume: impl Fn(usize) -> Option<usize>,
+    ) -> (Vec<Lexicon>, Option<usize>) {
+        let mut lexicons: Vec<Lexicon> = vec![];
         // loop through text
-        let mut current_lexicon = Lexicon::new(0);
+        let mut current_lexicon = Lexicon::new(start);
         // loop every character
-        let mut indices = g.text.char_indices().peekable();
+        let mut indices = text[start..]
+            .char_indices()
+            .map(|(i, c)| (i + start, c))
+            .peekable();
         while let Some((i, c)) = indices.next() {
+            // numbers, prices and dates contain punctuation
+            if let Some((len, kind)) = entity::scan(&text[i..], &text[..i]) {
+                while indices.next_if(|(j, _)| *j < i + len).is_some() {}
+                let mut lexeme = Lexeme::new(i);
+                lexeme.set_length(i + len);
+                lexeme.metadata.kind = kind;
+                current_lexicon.set_length(i + len);
+                current_lexicon.lexemes.push(lexeme);
+            }
             // check if character is punctuation
-            if self.punctuations.contains(&c) {
+            else if self.punctuations.contains(&c) {
                 // end of lexicon
                 current_lexicon.set_suffix(i + 1);
                 lexicons.push(current_lexicon);
+                if let Some(found) = resume(i + 1) {
+                    return (lexicons, Some(found));
+                }
                 current_lexicon = Lexicon::new(i + 1);
             }
             // check if character is whitespace
@@ -41,12 +70,12 @@ impl Tokenizer {
                 }
             } else {
                 // get until next character is whitespace or punctuation
-                let mut i2 = i;
+                let mut i2 = i + c.len_utf8();
                 while let Some((i, c)) = indices.peek() {
-                    if c.is_whitespace() || self.punctuations.contains(&c) {
+                    if c.is_whitespace() || self.punctuations.contains(c) || entity::is_emoji(*c) {
                         break;
                     } else {
-                        i2 = *i + 1;
+                        i2 = *i + c.len_utf8();
                         indices.next();
                     }
                 }
@@ -61,8 +90,6 @@ impl Tokenizer {
         if current_lexicon.lexemes.len() > 0 || current_lexicon.prefix > 0 {
             lexicons.push(current_lexicon);
         }
-
-        g.lexicons = lexicons;
-        g
+        (lexicons, None)
     }
 }
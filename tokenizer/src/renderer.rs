@@ -16,6 +16,34 @@ impl Tokenizer {
         text
     }
 
+    /// Renders the graph with every word inside the given `(offset, length)`
+    /// spans replaced by '*', except the first letter of each span.
+    pub fn render_masked(&self, graph: &Graph, spans: &[(usize, usize)]) -> String {
+        let mut text = String::new();
+        for lexicon in &graph.lexicons {
+            text.push_str(graph.get_lexicon_prefix(lexicon));
+            for lexeme in lexicon.lexemes.iter() {
+                let word = graph.get_word(lexeme);
+                let end = lexeme.offset + lexeme.length;
+                let span = spans
+                    .iter()
+                    .find(|(offset, length)| lexeme.offset < offset + length && *offset < end);
+                match span {
+                    Some((offset, _)) => {
+                        let keep = usize::from(lexeme.offset <= *offset);
+                        for (i, c) in word.chars().enumerate() {
+                            text.push(if i < keep { c } else { '*' });
+                        }
+                    }
+                    None => text.push_str(word),
+                }
+                text.push_str(graph.get_lexeme_suffix(lexeme));
+            }
+            text.push_str(graph.get_lexicon_suffix(lexicon));
+        }
+        text
+    }
+
     pub fn render_flat(&self, graph: &Graph) -> String {
         let mut text = String::new();
         for lexicon in &graph.lexicons {
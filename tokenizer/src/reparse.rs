@@ -0,0 +1,249 @@
+// Incremental parsing for edited texts. Only the lexicons around an edit are
+// parsed again, the ones after it are shifted and keep their keys.
+
+use graph::{Graph, Lexeme};
+
+use crate::Tokenizer;
+
+/// Replaces `length` bytes at `offset` with `text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub offset: usize,
+    pub length: usize,
+    pub text: String,
+}
+
+impl Tokenizer {
+    /// Parses `text`, an edited version of the graph's text, reusing the
+    /// lexicons the edit did not reach. None when the lexicons do not fit
+    /// the graph's text.
+    pub fn reparse(&self, graph: &Graph, text: String) -> Option<Graph> {
+        if !fits(graph) {
+            return None;
+        }
+        let old = graph.text.as_bytes();
+        let new = text.as_bytes();
+        let mut prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+        while !graph.text.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+        let max_suffix = old.len().min(new.len()) - prefix;
+        let mut suffix = old
+            .iter()
+            .rev()
+            .zip(new.iter().rev())
+            .take(max_suffix)
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !graph.text.is_char_boundary(old.len() - suffix) {
+            suffix -= 1;
+        }
+        let edit = TextEdit {
+            offset: prefix,
+            length: old.len() - suffix - prefix,
+            text: text[prefix..new.len() - suffix].to_owned(),
+        };
+        self.reparse_edit(graph, &edit, text)
+    }
+
+    /// Applies the edits one after another, each one at offsets into the
+    /// text the previous edits left. None when an edit is out of the text
+    /// or the lexicons do not fit it.
+    pub fn apply_edits(&self, graph: &Graph, edits: &[TextEdit]) -> Option<Graph> {
+        if !fits(graph) {
+            return None;
+        }
+        let mut graph = graph.clone();
+        for edit in edits {
+            let end = edit.offset.checked_add(edit.length)?;
+            graph.text.get(edit.offset..end)?;
+            let mut text = graph.text.clone();
+            text.replace_range(edit.offset..end, &edit.text);
+            graph = self.reparse_edit(&graph, edit, text)?;
+        }
+        Some(graph)
+    }
+
+    fn reparse_edit(&self, graph: &Graph, edit: &TextEdit, text: String) -> Option<Graph> {
+        let old_end = edit.offset + edit.length;
+        let new_end = edit.offset + edit.text.len();
+        let shift = |offset: usize| Some(offset.checked_sub(old_end)? + new_end);
+        // numbers like "1.2.3" or "Rp. 25.000" reach across the punctuation
+        // before the edit, so parsing starts a lexicon before the edited one
+        // and from there back to the whitespace in front of its number
+        let mut first = graph
+            .lexicons
+            .partition_point(|l| l.offset <= edit.offset)
+            .saturating_sub(2);
+        while first > 0 && graph.lexicons[first].prefix == 0 {
+            first -= 1;
+        }
+        let start = match first {
+            0 => 0,
+            _ => graph.lexicons[first].offset,
+        };
+        // past the edit, a lexicon which begins where an old one did is
+        // parsed the same way
+        let resume = |offset: usize| match offset >= new_end {
+            true => graph
+                .lexicons
+                .binary_search_by_key(&(offset - new_end + old_end), |l| l.offset)
+                .ok(),
+            false => None,
+        };
+        let (mut parsed, found) = self.parse_from(&text, start, resume);
+        let last = found.unwrap_or(graph.lexicons.len());
+        let old_lexemes: Vec<&Lexeme> = graph.lexicons[first..last]
+            .iter()
+            .flat_map(|l| &l.lexemes)
+            .collect();
+        let mut g = Graph::new(text, graph.using_keys);
+        // untouched words keep what is known about them, edited ones the key
+        for lexeme in parsed.iter_mut().flat_map(|l| l.lexemes.iter_mut()) {
+            let old_offset = match lexeme.offset {
+                o if o < edit.offset => Some(o),
+                o if o >= new_end => Some(o - new_end + old_end),
+                _ => None,
+            };
+            let old = old_offset.and_then(|o| {
+                let i = old_lexemes.binary_search_by_key(&o, |l| l.offset).ok()?;
+                Some(old_lexemes[i])
+            });
+            match old {
+                // "1" of "1.2.3a" was a word, of "1..3a" a number
+                Some(old)
+                    if graph.get_word(old) == g.get_word(lexeme)
+                        && old.metadata.kind == lexeme.metadata.kind =>
+                {
+                    lexeme.metadata = old.metadata.clone();
+                }
+                Some(old) if graph.using_keys => lexeme.set_key(old.metadata.key),
+                _ if graph.using_keys => lexeme.init_key(),
+                _ => {}
+            }
+        }
+        g.lexicons = graph.lexicons[..first].to_vec();
+        g.lexicons.append(&mut parsed);
+        for lexicon in &graph.lexicons[last..] {
+            let mut lexicon = lexicon.clone();
+            lexicon.offset = shift(lexicon.offset)?;
+            for lexeme in lexicon.lexemes.iter_mut() {
+                lexeme.offset = shift(lexeme.offset)?;
+            }
+            g.lexicons.push(lexicon);
+        }
+        Some(g)
+    }
+}
+
+/// Whether the lexicons and lexemes are in order and each one lies within
+/// the text, which lexicons sent by clients may not.
+pub fn fits(graph: &Graph) -> bool {
+    let text = &graph.text;
+    let mut end = 0;
+    for lexicon in &graph.lexicons {
+        let lexicon_end = lexicon
+            .offset
+            .checked_add(lexicon.prefix)
+            .and_then(|o| o.checked_add(lexicon.length))
+            .and_then(|o| o.checked_add(lexicon.suffix));
+        let Some(lexicon_end) = lexicon_end else {
+            return false;
+        };
+        if lexicon.offset < end || text.get(lexicon.offset..lexicon_end).is_none() {
+            return false;
+        }
+        end = lexicon.offset;
+        for lexeme in &lexicon.lexemes {
+            let word_end = lexeme.offset.checked_add(lexeme.length);
+            let lexeme_end = word_end.and_then(|o| o.checked_add(lexeme.suffix));
+            let (Some(word_end), Some(lexeme_end)) = (word_end, lexeme_end) else {
+                return false;
+            };
+            if lexeme.offset < end
+                || lexeme_end > lexicon_end
+                || text.get(lexeme.offset..word_end).is_none()
+            {
+                return false;
+            }
+            end = lexeme_end;
+        }
+        end = lexicon_end;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let tokenizer = Tokenizer::new();
+        let s = "Halo, apa kabar? Harganya Rp 25.000 saja. Rumah  baik ya!";
+        let mut graph = tokenizer.parse(s.to_owned());
+        graph.init_hash_keys();
+        let keys = |g: &Graph| -> Vec<(String, usize)> {
+            g.lexicons
+                .iter()
+                .flat_map(|l| &l.lexemes)
+                .map(|l| (g.get_word(l).to_owned(), l.metadata.key))
+                .collect()
+        };
+        let old = keys(&graph);
+        let edited = "Halo, apa kabar? Harganya Rp 25.000,50 saja. Rumah  baik ya!";
+        let new = tokenizer.reparse(&graph, edited.to_owned()).unwrap();
+        let mut stripped = new.clone();
+        stripped.strip_hash_keys();
+        let expected = tokenizer.parse(edited.to_owned());
+        assert_eq!(
+            tokenizer.to_json(&stripped).unwrap(),
+            tokenizer.to_json(&expected).unwrap()
+        );
+        let new = keys(&new);
+        assert_eq!(new[4].0, "Rp 25.000,50");
+        assert_eq!(new[4].1, old[4].1);
+        assert_eq!(new[..4], old[..4]);
+        assert_eq!(new[5..], old[5..]);
+
+        // "12." and "30 WIB" become one time
+        let graph = tokenizer.parse("Pukul 12. Sampai".to_owned());
+        let edits = [TextEdit {
+            offset: 9,
+            length: 0,
+            text: "30 WIB".to_owned(),
+        }];
+        let new = tokenizer.apply_edits(&graph, &edits).unwrap();
+        let expected = tokenizer.parse("Pukul 12.30 WIB Sampai".to_owned());
+        assert_eq!(
+            tokenizer.to_json(&new).unwrap(),
+            tokenizer.to_json(&expected).unwrap()
+        );
+        let edits = [TextEdit {
+            offset: 99,
+            length: 0,
+            text: "!".to_owned(),
+        }];
+        assert!(tokenizer.apply_edits(&graph, &edits).is_none());
+
+        // deleting any char gives what a full parse would, "1.2.3a" included
+        let s = "Versi 1.2.3a rilis. Harga Rp. 1.500,- per 3.14 kg.";
+        let graph = tokenizer.parse(s.to_owned());
+        for (i, c) in s.char_indices() {
+            let mut edited = s.to_owned();
+            edited.replace_range(i..i + c.len_utf8(), "");
+            let new = tokenizer.reparse(&graph, edited.clone()).unwrap();
+            let expected = tokenizer.parse(edited);
+            assert_eq!(
+                tokenizer.to_json(&new).unwrap(),
+                tokenizer.to_json(&expected).unwrap()
+            );
+        }
+
+        // lexicons of another text are refused
+        let mut graph = tokenizer.parse("Halo dunia".to_owned());
+        graph.text = "Halo".to_owned();
+        assert!(tokenizer.reparse(&graph, "Halo!".to_owned()).is_none());
+        assert!(tokenizer.apply_edits(&graph, &[]).is_none());
+    }
+}
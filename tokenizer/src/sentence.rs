@@ -0,0 +1,174 @@
+use graph::{Graph, Sentence};
+
+use crate::Tokenizer;
+
+// abbreviations followed by a name, an address or a number,
+// a period after them never ends a sentence
+#[rustfmt::skip]
+const TITLES: &[&str] = &[
+    "jl", "jln", "gg", "no", "rt", "rw", "kel", "kec", "kab", "prov", "dr", "drg", "drs", "dra",
+    "prof", "ir", "hj", "kh", "sdr", "sdri", "bpk", "bp", "yth", "pt", "cv", "tbk", "rp", "hlm",
+    "tgl", "telp", "tel", "kol", "jend", "brigjen", "mayjen", "letjen", "kapt", "lt", "mr", "mrs",
+    "ms", "ny", "nn", "tn", "st", "vol",
+];
+
+// abbreviations which may end a sentence as well
+#[rustfmt::skip]
+const CLOSINGS: &[&str] = &[
+    "dll", "dsb", "dst", "dkk", "sda", "etc", "tsb",
+];
+
+const QUOTES: &[char] = &['"', '\'', ')', ']', '}'];
+
+impl Tokenizer {
+    /// Fills `graph.sentences` by grouping its lexicons into sentences.
+    pub fn segment(&self, graph: &mut Graph) {
+        graph.sentences = self.find_sentences(graph);
+    }
+
+    pub fn find_sentences(&self, graph: &Graph) -> Vec<Sentence> {
+        let lexicons = &graph.lexicons;
+        let mut sentences = vec![];
+        let mut start = 0;
+        let mut i = 0;
+        while i < lexicons.len() {
+            let mut end = i + 1;
+            if is_sentence_end(graph, i) {
+                // closing quotes and repeated punctuation stay in the sentence
+                while end < lexicons.len()
+                    && lexicons[end].lexemes.is_empty()
+                    && lexicons[end].prefix == 0
+                {
+                    end += 1;
+                }
+                let quoted = lexicons[i + 1..end]
+                    .iter()
+                    .any(|l| graph.get_lexicon_suffix(l).contains(QUOTES));
+                // "Pergi!" katanya.
+                let continued = quoted && starts_lowercase(next_word(graph, end));
+                if !continued {
+                    push_sentence(graph, &mut sentences, start, end);
+                    start = end;
+                }
+            }
+            i = end;
+        }
+        if start < lexicons.len() {
+            push_sentence(graph, &mut sentences, start, lexicons.len());
+        }
+        sentences
+    }
+}
+
+fn is_sentence_end(graph: &Graph, i: usize) -> bool {
+    let lexicon = &graph.lexicons[i];
+    let suffix = graph.get_lexicon_suffix(lexicon);
+    if suffix.contains(['!', '?']) {
+        return true;
+    }
+    if !suffix.contains('.') {
+        return false;
+    }
+    // numbers, urls and emails (1.500, benerin.web.id)
+    if let Some(next) = graph.lexicons.get(i + 1) {
+        if next.prefix == 0 && !next.lexemes.is_empty() {
+            return false;
+        }
+    }
+    let word = match lexicon.lexemes.last() {
+        Some(lexeme) => graph.get_word(lexeme),
+        None => return true,
+    };
+    let lower = word.to_lowercase();
+    if TITLES.contains(&lower.as_str()) {
+        return false;
+    }
+    // initials (B. J. Habibie)
+    let mut chars = word.chars();
+    if chars.next().is_some_and(|c| c.is_uppercase()) && chars.next().is_none() {
+        return false;
+    }
+    if CLOSINGS.contains(&lower.as_str()) {
+        return !starts_lowercase(next_word(graph, i + 1));
+    }
+    true
+}
+
+fn next_word(graph: &Graph, from: usize) -> Option<&str> {
+    graph.lexicons[from..]
+        .iter()
+        .find_map(|l| l.lexemes.first())
+        .map(|l| graph.get_word(l))
+}
+
+fn starts_lowercase(word: Option<&str>) -> bool {
+    word.and_then(|w| w.chars().next())
+        .is_some_and(|c| c.is_lowercase())
+}
+
+fn push_sentence(graph: &Graph, sentences: &mut Vec<Sentence>, start: usize, end: usize) {
+    let first = &graph.lexicons[start];
+    let last = &graph.lexicons[end - 1];
+    let stop = last.offset + last.prefix + last.length + last.suffix;
+    let has_words = graph.lexicons[start..end]
+        .iter()
+        .any(|l| !l.lexemes.is_empty());
+    // leftover punctuation or whitespace joins the previous sentence
+    match sentences.last_mut() {
+        Some(prev) if !has_words => {
+            prev.lexicons = end - prev.lexicon;
+            prev.length = graph.text[prev.offset..stop].trim_end().len();
+        }
+        _ => {
+            let offset = first.offset + first.prefix;
+            sentences.push(Sentence {
+                offset,
+                length: graph.text[offset..stop].trim_end().len(),
+                lexicon: start,
+                lexicons: end - start,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentences(text: &str) -> Vec<String> {
+        let tokenizer = Tokenizer::new();
+        let graph = tokenizer.parse(text.to_owned());
+        tokenizer
+            .find_sentences(&graph)
+            .iter()
+            .map(|s| graph.text[s.offset..s.offset + s.length].to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn it_works() {
+        assert_eq!(
+            sentences("Saya tinggal di Jl. Merdeka no. 5. Harganya Rp 1.500,00 saja! "),
+            vec![
+                "Saya tinggal di Jl. Merdeka no. 5.",
+                "Harganya Rp 1.500,00 saja!"
+            ]
+        );
+        assert_eq!(
+            sentences("Prof. Dr. B. J. Habibie lahir di Parepare. Beliau presiden ketiga."),
+            vec![
+                "Prof. Dr. B. J. Habibie lahir di Parepare.",
+                "Beliau presiden ketiga."
+            ]
+        );
+        assert_eq!(
+            sentences("\"Pergi!\" katanya. Dia membeli buku, pensil, dll. Lalu pulang..."),
+            vec![
+                "\"Pergi!\" katanya.",
+                "Dia membeli buku, pensil, dll.",
+                "Lalu pulang..."
+            ]
+        );
+        assert_eq!(sentences(""), Vec::<String>::new());
+    }
+}